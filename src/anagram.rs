@@ -0,0 +1,164 @@
+//! Rounds of dealt letters where players race to make the longest word from them.
+
+use std::collections::{hash_map::Entry, HashMap};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::model::channel::Message;
+use serenity::model::id::{ChannelId, UserId};
+use serenity::prelude::TypeMapKey;
+use tokio::sync::Mutex;
+
+use crate::http::HttpClient;
+use crate::wordgame::{gen_random_chars, GuessError, Mode, Points, Table, WordGameState};
+use crate::wordlist::WordList;
+
+pub const RACK_SIZES: RangeInclusive<usize> = 6..=12;
+pub const DEFAULT_RACK_SIZE: usize = 9;
+pub const ROUNDS: usize = 5;
+pub const ROUND_TIME: Duration = Duration::from_secs(40);
+const MIN_LETTERS: u64 = 3;
+
+/// Deals a new rack as a game of its own, where words can use the letters in any order
+///
+/// The message of the round has to be set once the rack has been shown.
+fn deal(rack_size: usize, starter: UserId) -> WordGameState {
+    let rack = Table::rack(gen_random_chars(rack_size), MIN_LETTERS);
+    WordGameState::new(rack, None, None, Mode::Free, starter, Message::default())
+}
+
+/// The winner of a round
+pub struct RoundWinner {
+    pub user: UserId,
+    pub word: String,
+    pub points: u128,
+}
+
+pub struct AnagramGame {
+    pub rack_size: usize,
+    /// The current round, which checks the words
+    pub round: WordGameState,
+    /// Counted from 1
+    pub round_number: usize,
+    /// The words accepted this round in the order they were posted
+    found: Vec<(UserId, String)>,
+    /// The points of each player from all the rounds so far
+    pub totals: HashMap<UserId, Points>,
+}
+
+impl AnagramGame {
+    pub fn new(rack_size: usize, starter: UserId) -> Self {
+        AnagramGame {
+            rack_size,
+            round: deal(rack_size, starter),
+            round_number: 1,
+            found: Vec::new(),
+            totals: HashMap::new(),
+        }
+    }
+    pub fn is_last_round(&self) -> bool {
+        self.round_number >= ROUNDS
+    }
+    pub async fn guess_word(&mut self, client: &HttpClient, word_list: &WordList, user: UserId, word: String) -> Result<(), GuessError> {
+        self.round.guess_word(client, word_list, user, word.clone()).await?;
+        self.found.push((user, word));
+        Ok(())
+    }
+    /// Gives the points of the round to whoever posted the longest word first
+    pub fn end_round(&mut self) -> Option<RoundWinner> {
+        let longest = self.found.iter().map(|(_, w)| w.chars().count()).max()?;
+        let (user, word) = self.found.iter().find(|(_, w)| w.chars().count() == longest)?.clone();
+        let points = self.round.scoring.score(&self.round.table, &word);
+
+        let total = self.totals.entry(user).or_default();
+        total.points += points;
+        total.words += 1;
+        total.letters += longest as u64;
+        if longest > total.longest_word.chars().count() {
+            total.longest_word.clone_from(&word);
+        }
+
+        Some(RoundWinner { user, word, points })
+    }
+    /// Deals the letters of the next round
+    pub fn next_round(&mut self) {
+        self.round = deal(self.rack_size, self.round.starter);
+        self.round_number += 1;
+        self.found.clear();
+    }
+    /// The players ranked by their points from all rounds, best first
+    pub fn ranking(&self) -> Vec<(UserId, &Points)> {
+        let mut ranking: Vec<_> = self.totals.iter().map(|(&u, p)| (u, p)).collect();
+        ranking.sort_by(|(_, a), (_, b)| b.points.cmp(&a.points).then(b.letters.cmp(&a.letters)));
+        ranking
+    }
+}
+
+/// A game that can be shared between the message handler and the timer of its rounds
+pub type SharedAnagram = Arc<Mutex<AnagramGame>>;
+
+#[derive(Default)]
+/// The anagram games being played, one for each channel
+pub struct AnagramGames {
+    games: HashMap<ChannelId, SharedAnagram>,
+}
+
+impl TypeMapKey for AnagramGames {
+    type Value = Self;
+}
+
+impl AnagramGames {
+    pub fn get(&self, channel_id: ChannelId) -> Option<SharedAnagram> {
+        self.games.get(&channel_id).cloned()
+    }
+    pub fn contains(&self, channel_id: ChannelId) -> bool {
+        self.games.contains_key(&channel_id)
+    }
+    /// Starts the game in the channel of its first round, unless there is a game there already
+    pub fn start(&mut self, game: AnagramGame) -> Option<SharedAnagram> {
+        match self.games.entry(game.round.message.channel_id) {
+            Entry::Occupied(_) => None,
+            Entry::Vacant(v) => Some(v.insert(Arc::new(Mutex::new(game))).clone()),
+        }
+    }
+    /// Removes the game from the channel only if it is still the given game
+    pub fn remove_game(&mut self, channel_id: ChannelId, game: &SharedAnagram) -> bool {
+        match self.games.entry(channel_id) {
+            Entry::Occupied(o) if Arc::ptr_eq(o.get(), game) => {
+                o.remove();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serenity::model::id::UserId;
+
+    use super::{AnagramGame, MIN_LETTERS};
+
+    #[test]
+    fn longest_word_first_wins() {
+        let (anna, jogvan) = (UserId::new(1), UserId::new(2));
+        let mut game = AnagramGame::new(9, anna);
+
+        assert_eq!(game.round.table.cells().len(), 9);
+        assert_eq!(game.round.table.min_letters(), MIN_LETTERS);
+        assert!(game.end_round().is_none());
+
+        game.found = vec![(anna, "ró".to_owned()), (jogvan, "hest".to_owned()), (anna, "seyð".to_owned())];
+        let winner = game.end_round().unwrap();
+        assert_eq!((winner.user, &*winner.word, winner.points), (jogvan, "hest", 4));
+
+        game.next_round();
+        assert_eq!(game.round_number, 2);
+        game.found = vec![(anna, "hestur".to_owned())];
+        game.end_round();
+
+        let ranking: Vec<_> = game.ranking().into_iter().map(|(u, p)| (u, p.points, p.words)).collect();
+        assert_eq!(ranking, vec![(anna, 16, 1), (jogvan, 4, 1)]);
+    }
+}
@@ -0,0 +1,235 @@
+//! Pictures of word game boards, which show better than the text board on small screens.
+
+use std::{env, fs};
+
+use ab_glyph::{point, Font, FontArc, PxScale};
+use png::{BitDepth, ColorType, Encoder};
+use serenity::prelude::TypeMapKey;
+
+use crate::wordgame::Table;
+
+/// Where to look for a font if `ORDABOT_BOARD_FONT` isn't set
+const FONT_PATHS: [&str; 3] = [
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans-Bold.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans-Bold.ttf",
+];
+
+/// Side length of a tile in pixels
+const TILE: u32 = 96;
+/// Space between the tiles and around the board
+const GAP: u32 = 12;
+const CORNER_RADIUS: f32 = 12.;
+const PATH_WIDTH: f32 = 14.;
+/// How much the path covers the letters it goes through
+const PATH_OPACITY: f32 = 0.7;
+const LETTER_SIZE: f32 = 64.;
+
+type Colour = [u8; 3];
+
+/// The background of Discord's dark theme, so the board blends in
+const BACKGROUND: Colour = [0x31, 0x33, 0x38];
+const TILE_COLOUR: Colour = [0xf2, 0xe8, 0xcf];
+const HIGHLIGHTED_TILE: Colour = [0xf5, 0xc4, 0x5e];
+const PATH_COLOUR: Colour = [0xd9, 0x48, 0x3b];
+const LETTER_COLOUR: Colour = [0x22, 0x22, 0x22];
+
+#[derive(Clone, Default)]
+/// Paints boards if a font could be found, otherwise only the text board can be shown
+pub struct BoardPainter {
+    font: Option<FontArc>,
+}
+
+impl TypeMapKey for BoardPainter {
+    type Value = Self;
+}
+
+impl BoardPainter {
+    /// Loads the font given by `ORDABOT_BOARD_FONT` or a common system font
+    pub fn load() -> Self {
+        let font = env::var("ORDABOT_BOARD_FONT").into_iter()
+            .chain(FONT_PATHS.iter().map(|s| s.to_string()))
+            .find_map(|path| FontArc::try_from_vec(fs::read(path).ok()?).ok());
+
+        if font.is_none() {
+            eprintln!("No font found for word game boards, they will only be shown as text");
+        }
+        BoardPainter { font }
+    }
+    /// Paints the table as a PNG with the cells of `path` highlighted and joined up in order,
+    /// or `None` if there is no font
+    pub fn paint(&self, table: &Table, path: &[usize]) -> Option<Vec<u8>> {
+        let font = self.font.as_ref()?;
+
+        let mut canvas = draw_board(table, path);
+        draw_letters(&mut canvas, table, font);
+        Some(canvas.encode_png())
+    }
+}
+
+/// An RGB picture
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<Colour>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, colour: Colour) -> Self {
+        Canvas { width, height, pixels: vec![colour; (width * height) as usize] }
+    }
+    /// Mixes the colour into the pixel by how much of it is covered, from 0 to 1
+    fn blend(&mut self, x: i64, y: i64, colour: Colour, coverage: f32) {
+        if !(0..self.width as i64).contains(&x) || !(0..self.height as i64).contains(&y) {
+            return;
+        }
+        let coverage = coverage.clamp(0., 1.);
+        let pixel = &mut self.pixels[(y as u32 * self.width + x as u32) as usize];
+
+        for (p, c) in pixel.iter_mut().zip(colour) {
+            *p = (*p as f32 * (1. - coverage) + c as f32 * coverage).round() as u8;
+        }
+    }
+    /// Fills the pixels in the box by the signed distance to the edge of a shape, negative inside,
+    /// which smooths the edges
+    fn fill_shape<F: Fn(f32, f32) -> f32>(&mut self, (x0, y0): (f32, f32), (x1, y1): (f32, f32), colour: Colour, opacity: f32, distance: F) {
+        for y in y0.floor() as i64..=y1.ceil() as i64 {
+            for x in x0.floor() as i64..=x1.ceil() as i64 {
+                let d = distance(x as f32 + 0.5, y as f32 + 0.5);
+                if d < 0.5 {
+                    self.blend(x, y, colour, opacity * (0.5 - d).min(1.));
+                }
+            }
+        }
+    }
+    fn rounded_square(&mut self, x: f32, y: f32, size: f32, radius: f32, colour: Colour, opacity: f32) {
+        let half = size / 2.;
+        let (cx, cy) = (x + half, y + half);
+
+        self.fill_shape((x, y), (x + size, y + size), colour, opacity, |px, py| {
+            let dx = ((px - cx).abs() - half + radius).max(0.);
+            let dy = ((py - cy).abs() - half + radius).max(0.);
+            (dx * dx + dy * dy).sqrt() - radius
+        });
+    }
+    /// A line with round ends
+    fn line(&mut self, (ax, ay): (f32, f32), (bx, by): (f32, f32), width: f32, colour: Colour, opacity: f32) {
+        let r = width / 2.;
+        let (dx, dy) = (bx - ax, by - ay);
+        let length2 = (dx * dx + dy * dy).max(f32::EPSILON);
+
+        self.fill_shape((ax.min(bx) - r, ay.min(by) - r), (ax.max(bx) + r, ay.max(by) + r), colour, opacity, |px, py| {
+            let t = (((px - ax) * dx + (py - ay) * dy) / length2).clamp(0., 1.);
+            let (nx, ny) = (ax + t * dx - px, ay + t * dy - py);
+            (nx * nx + ny * ny).sqrt() - r
+        });
+    }
+    fn encode_png(&self) -> Vec<u8> {
+        let mut png = Vec::new();
+        let mut encoder = Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(ColorType::Rgb);
+        encoder.set_depth(BitDepth::Eight);
+
+        let data: Vec<u8> = self.pixels.iter().flatten().copied().collect();
+        // Writing to memory can't fail and the size always matches
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&data).unwrap();
+        writer.finish().unwrap();
+
+        png
+    }
+}
+
+/// The top left corner of the cell's tile
+fn tile_corner(table: &Table, i: usize) -> (f32, f32) {
+    let (x, y) = ((i % table.width()) as u32, (i / table.width()) as u32);
+    ((GAP + x * (TILE + GAP)) as f32, (GAP + y * (TILE + GAP)) as f32)
+}
+fn tile_centre(table: &Table, i: usize) -> (f32, f32) {
+    let (x, y) = tile_corner(table, i);
+    (x + TILE as f32 / 2., y + TILE as f32 / 2.)
+}
+
+/// Draws the tiles and the path, leaving out the letters
+fn draw_board(table: &Table, path: &[usize]) -> Canvas {
+    let side = GAP + table.width() as u32 * (TILE + GAP);
+    let mut canvas = Canvas::new(side, side, BACKGROUND);
+
+    for i in 0..table.cells().len() {
+        let (x, y) = tile_corner(table, i);
+        let colour = if path.contains(&i) { HIGHLIGHTED_TILE } else { TILE_COLOUR };
+        canvas.rounded_square(x, y, TILE as f32, CORNER_RADIUS, colour, 1.);
+    }
+
+    for step in path.windows(2) {
+        canvas.line(tile_centre(table, step[0]), tile_centre(table, step[1]), PATH_WIDTH, PATH_COLOUR, PATH_OPACITY);
+    }
+    // Mark where the word starts
+    if let Some(&first) = path.first() {
+        let (x, y) = tile_centre(table, first);
+        canvas.rounded_square(x - PATH_WIDTH, y - PATH_WIDTH, 2. * PATH_WIDTH, PATH_WIDTH, PATH_COLOUR, PATH_OPACITY);
+    }
+
+    canvas
+}
+
+/// Draws the letters of the table in upper case in the middle of their tiles
+fn draw_letters(canvas: &mut Canvas, table: &Table, font: &FontArc) {
+    let scale = PxScale::from(LETTER_SIZE);
+    // Letters are centred by capital height, so accents stick out above instead of pushing letters down
+    let cap_height = font.outline_glyph(font.glyph_id('H').with_scale(scale))
+        .map_or(LETTER_SIZE * 0.7, |g| g.px_bounds().height());
+
+    for (i, &c) in table.cells().iter().enumerate() {
+        let Some(c) = c.to_uppercase().next() else { continue };
+        let Some(glyph) = font.outline_glyph(font.glyph_id(c).with_scale_and_position(scale, point(0., 0.))) else { continue };
+
+        let bounds = glyph.px_bounds();
+        let (cx, cy) = tile_centre(table, i);
+        let left = (cx - bounds.width() / 2.).round() as i64;
+        let top = (cy + cap_height / 2. + bounds.min.y).round() as i64;
+
+        glyph.draw(|x, y, coverage| canvas.blend(left + x as i64, top + y as i64, LETTER_COLOUR, coverage));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{draw_board, tile_centre, tile_corner, HIGHLIGHTED_TILE, PATH_COLOUR, TILE_COLOUR, BACKGROUND, GAP, TILE};
+    use crate::wordgame::Table;
+
+    fn table() -> Table {
+        Table::new(4, "hestarumkønilado".chars().collect())
+    }
+
+    #[test]
+    fn tiles_and_path() {
+        let table = table();
+        let canvas = draw_board(&table, &[0, 5, 10]);
+        let at = |(x, y): (f32, f32)| canvas.pixels[(y as u32 * canvas.width + x as u32) as usize];
+        // Near the bottom left corner of the tile, away from the rounding and the diagonal path
+        let inside = |i| {
+            let (x, y) = tile_corner(&table, i);
+            at((x + 16., y + TILE as f32 - 16.))
+        };
+
+        assert_eq!(canvas.width, GAP + 4 * (TILE + GAP));
+        assert_eq!(at((1., 1.)), BACKGROUND);
+        assert_eq!(inside(0), HIGHLIGHTED_TILE);
+        assert_eq!(inside(10), HIGHLIGHTED_TILE);
+        assert_eq!(inside(3), TILE_COLOUR);
+        // The path is see-through, so it's only mostly its own colour
+        let [r, g, b] = at(tile_centre(&table, 5));
+        assert!(r.abs_diff(PATH_COLOUR[0]) < 20 && g.abs_diff(PATH_COLOUR[1]) < 40 && b.abs_diff(PATH_COLOUR[2]) < 40);
+        assert_eq!(at(tile_centre(&table, 3)), TILE_COLOUR);
+    }
+    #[test]
+    fn encodes_png() {
+        let png = draw_board(&table(), &[]).encode_png();
+        let decoder = png::Decoder::new(std::io::Cursor::new(png));
+        let reader = decoder.read_info().unwrap();
+
+        assert_eq!(reader.info().width, GAP + 4 * (TILE + GAP));
+        assert_eq!(reader.info().height, reader.info().width);
+    }
+}
@@ -1,14 +1,12 @@
 #![allow(dead_code)]
 
 use std::num::NonZeroUsize;
-use reqwest::{
-    Response,
-    get as reqwest_get
-};
 use serde::{Deserialize, Deserializer};
-use scraper::{Html, Node};
+use scraper::{ElementRef, Html, Node};
 
-use crate::util::{MsgBunchBuilder, MsgBunch, to_subscript, to_superscript, split_trim};
+use crate::http::{HttpClient, HttpResponse};
+use crate::render::{CalculatedStyle, Renderer, DiscordRenderer};
+use crate::util::{Bunch, BunchBuilder};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct SprotinResponse {
@@ -109,16 +107,6 @@ struct SprotinDictionary {
     total_searches: u64,
 }
 
-#[derive(Debug, Copy, Clone, Default)]
-struct CalculatedStyle {
-    bold: bool,
-    italics: bool,
-    underline: bool,
-    strikethrough: bool,
-    superscript: bool,
-    subscript: bool,
-}
-
 #[derive(Debug, Copy, Clone)]
 struct Style {
     bold: Option<bool>,
@@ -204,118 +192,16 @@ impl std::ops::BitOr for Style {
     }
 }
 
-#[derive(Debug, Clone)]
-struct DiscordStylisedTextBuilder {
-    buf: String,
-    last_style: CalculatedStyle,
-    last_whitespace_length: usize,
-}
-
-impl DiscordStylisedTextBuilder {
-    fn new() -> Self {
-        DiscordStylisedTextBuilder {
-            buf: String::new(),
-            last_style: CalculatedStyle::default(),
-            last_whitespace_length: 0,
-        }
-    }
-    fn push_str(&mut self, s: &str, style: CalculatedStyle) {
-        use std::borrow::Cow;
-        let c: Cow<_>;
-        if style.superscript {
-            c = to_superscript(s).into();
-        } else if style.subscript {
-            c = to_subscript(s).into();
-        } else {
-            c = s.into();
-        }
-
-        fn changed(a: bool, b: bool) -> Option<bool> {
-            if a == b {
-                None
-            } else {
-                Some(b)
-            }
-        }
-        fn tos(s: &str, b: Option<bool>, start: bool) -> &str {
-            match (start, b) {
-                (true, Some(true)) => s,
-                (false, Some(false)) => s,
-                _ => "",
-            } 
-        }
-
-        let bold_changed = changed(self.last_style.bold, style.bold);
-        let italics_changed = changed(self.last_style.italics, style.italics);
-        let underline_changed = changed(self.last_style.underline, style.underline);
-        let strikethrough_changed = changed(self.last_style.strikethrough, style.strikethrough);
-
-        let any_changed = bold_changed.is_some() || italics_changed.is_some() || underline_changed.is_some() || strikethrough_changed.is_some();
-
-        let s = c.as_ref();
-
-        if !any_changed {
-            self.buf.push_str(s);
-            self.last_whitespace_length = s.rfind(|c: char| !c.is_whitespace()).map(|i| s.len() - i - 1).unwrap_or_else(|| s.len());
-        } else {
-            let (start_whitespace, text, end_whitespace) = split_trim(s);
-            let start_whitespace = self.buf.split_off(self.buf.len() - self.last_whitespace_length) + start_whitespace;
-
-            self.buf.push_str(tos("__", underline_changed, false));
-            self.buf.push_str(tos("**", bold_changed, false));
-            self.buf.push_str(tos("_", italics_changed, false));
-            self.buf.push_str(tos("~~", strikethrough_changed, false));
-
-            self.buf.push_str(&start_whitespace);
-
-            self.buf.push_str(tos("~~", strikethrough_changed, true));
-            self.buf.push_str(tos("_", italics_changed, true));
-            self.buf.push_str(tos("**", bold_changed, true));
-            self.buf.push_str(tos("__", underline_changed, true));
-            self.buf.push_str(text);
-            self.buf.push_str(end_whitespace);
-
-            self.last_whitespace_length = end_whitespace.len();
-        }
-
-        self.last_style = style;
-    }
-    fn build(self) -> String {
-        let DiscordStylisedTextBuilder {
-            last_style,
-            mut buf,
-            last_whitespace_length: _,
-        } = self;
-
-        if last_style.underline {
-            buf.push_str("__")
-        }
-        if last_style.italics {
-            buf.push('_')
-        }
-        if last_style.bold {
-            buf.push_str("**")
-        }
-        if last_style.strikethrough {
-            buf.push_str("~~")
-        }
-        
-        buf
-    }
-}
-
-fn parse_children(ret: &mut DiscordStylisedTextBuilder, children: ::ego_tree::iter::Children<Node>, style: Style) {
+fn parse_children<R: Renderer>(ret: &mut R, children: ::ego_tree::iter::Children<Node>, style: Style) {
     for child in children {
         match child.value() {
             Node::Element(elem) => {
-                let elem_style = style | Style::from_element_name(dbg!(elem.name()));
-                let style = elem.classes().fold(elem_style, |acc, b| Style::from_class(dbg!(b)) | acc);
+                let elem_style = style | Style::from_element_name(elem.name());
+                let style = elem.classes().fold(elem_style, |acc, b| Style::from_class(b) | acc);
                 parse_children(ret, child.children(), style)
             }
             Node::Text(text) => {
-                let s = text.text.to_string();
-
-                ret.push_str(&dbg!(s), style.calculate());
+                ret.push_str(&text.text, style.calculate());
             }
             _ => ()
         }
@@ -325,17 +211,33 @@ fn parse_children(ret: &mut DiscordStylisedTextBuilder, children: ::ego_tree::it
     }
 }
 
-// TODO interpret different classes appropriately (this rn just ignores all html tags and just retrieves the raw text)
-fn html_to_discord_markup(s: &str, style: Style) -> String {
-    let mut ret = DiscordStylisedTextBuilder::new();
+/// Renders Sprotin's html using the given renderer
+fn render_html<R: Renderer>(s: &str, style: Style) -> String {
+    let mut ret = R::default();
 
-    eprintln!("{}", s);
     let html = Html::parse_fragment(s);
     parse_children(&mut ret, html.tree.root().children(), style);
 
     ret.build()
 }
 
+/// The text of each element with the class in Sprotin's html, in order and with whitespace collapsed
+fn texts_with_class(s: &str, class: &str) -> Vec<String> {
+    let html = Html::parse_fragment(s);
+
+    html.tree.root().descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|e| e.value().classes().any(|c| c == class))
+        .map(|e| e.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" "))
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+#[inline(always)]
+fn html_to_discord_markup(s: &str, style: Style) -> String {
+    render_html::<DiscordRenderer>(s, style)
+}
+
 fn deserialize_optional_vec<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<String>, D::Error> {
     Option::<Vec<String>>::deserialize(d).map(Option::unwrap_or_default)
 }
@@ -441,7 +343,7 @@ impl SprotinWord {
         s
     }
 
-    pub fn to_full_string(&self, mmb: &mut MsgBunchBuilder) {
+    pub fn to_full_string<B: Bunch>(&self, mmb: &mut BunchBuilder<B>) {
         mmb.begin_section();
         if let Some(prepend_word) = &self.prepend_word {
             eprintln!("prepend_word: {}", prepend_word);
@@ -458,11 +360,25 @@ impl SprotinWord {
         if !self.inflected_form.is_empty() {
             mmb.begin_section().add_string(&self.inflection_table()).add_string("\n").end_section();
         }
+    }
 
-        for msg in &mmb.inner.messages {
-            eprint!("{}", msg);
-        }
-        eprintln!();
+    /// The inflexional categories, like the word class, in Discord markup
+    pub fn inflex_cats(&self) -> Option<String> {
+        self.inflex_cats.as_ref().map(|c| html_to_discord_markup(c, ITALICS))
+    }
+    /// The definitions in the explanation as plain text, without the examples
+    pub fn definitions(&self) -> Vec<String> {
+        texts_with_class(&self.explanation, "_h")
+            .into_iter()
+            .map(|d| d.trim_end_matches([',', ' ']).to_owned())
+            .collect()
+    }
+    /// The examples of use in the explanation as plain text
+    pub fn examples(&self) -> Vec<String> {
+        texts_with_class(&self.explanation, "_d")
+            .into_iter()
+            .map(|d| d.trim_end_matches([',', ' ']).to_owned())
+            .collect()
     }
 
     // TODO kinda hacky, but done after the JS making the tables on Sprotin itself
@@ -560,8 +476,8 @@ hvørsfall/gen   | {:m$} | {:f$} | {:n$} |
 
 #[cfg(not(feature = "from_res_error_resolve"))]
 #[inline(always)]
-async fn from_res(res: Response) -> SprotinResponse {
-    res.json().await.unwrap()
+async fn from_res(res: HttpResponse) -> Result<SprotinResponse, u16> {
+    res.json().await
 }
 #[cfg(feature = "from_res_error_resolve")]
 fn from_res(res: reqwest::blocking::Response) -> SprotinResponse {
@@ -586,17 +502,17 @@ fn from_res(res: reqwest::blocking::Response) -> SprotinResponse {
     }
 }
 
-pub async fn search(dictionary_id: u8, dictionary_page: u16, search_for: &str, search_inflections: bool, search_descriptions: bool) -> Result<SprotinResponse, u16> {
+pub async fn search(client: &HttpClient, dictionary_id: u8, dictionary_page: u16, search_for: &str, search_inflections: bool, search_descriptions: bool) -> Result<SprotinResponse, u16> {
     // This one doesn't seem to make a difference
     const SKIP_OTHER_DICTIONARIES_RESULTS: bool = true;
     // This is one gives us similar word suggestions if no results were found
     const SKIP_SIMILAR_WORDS: bool = false;
 
-    let res = reqwest_get(&format!("https://sprotin.fo/dictionary_search_json.php?DictionaryId={}&DictionaryPage={}&SearchFor={}&SearchInflections={}&SearchDescriptions={}&Group={}&SkipOtherDictionariesResults={}&SkipSimilarWords={}",
-        dictionary_id, dictionary_page, search_for, search_inflections as u8, search_descriptions as u8, "", SKIP_OTHER_DICTIONARIES_RESULTS as u8, SKIP_SIMILAR_WORDS as u8)).await.unwrap();
+    let res = client.send(client.get(format!("https://sprotin.fo/dictionary_search_json.php?DictionaryId={}&DictionaryPage={}&SearchFor={}&SearchInflections={}&SearchDescriptions={}&Group={}&SkipOtherDictionariesResults={}&SkipSimilarWords={}",
+        dictionary_id, dictionary_page, search_for, search_inflections as u8, search_descriptions as u8, "", SKIP_OTHER_DICTIONARIES_RESULTS as u8, SKIP_SIMILAR_WORDS as u8))).await?;
 
     if res.status().is_success() {
-        Ok(from_res(res).await)
+        from_res(res).await
     } else {
         Err(res.status().as_u16())
     }
@@ -633,14 +549,14 @@ fn dictionary_name(i: u32) -> &'static str {
 }
 
 impl SprotinResponse {
-    pub fn word(&self, word_nr: NonZeroUsize) -> Option<MsgBunch> {
-        let mut mmb = MsgBunchBuilder::new();
+    pub fn word<B: Bunch>(&self, word_nr: NonZeroUsize) -> Option<B> {
+        let mut mmb = BunchBuilder::new();
 
         self.words.get(word_nr.get()-1)?.to_full_string(&mut mmb);
 
         Some(mmb.build())
     }
-    pub fn summary(self) -> MsgBunch {
+    pub fn summary<B: Bunch>(self) -> B {
         let SprotinResponse {
             message,
             status,
@@ -664,7 +580,7 @@ impl SprotinResponse {
             dbg!(single_word);
         }
 
-        let mut mmb = MsgBunchBuilder::new();
+        let mut mmb = BunchBuilder::new();
 
         mmb.begin_section();
 
@@ -704,4 +620,39 @@ impl SprotinResponse {
         }
         mmb.build()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_html, texts_with_class, EMPTY};
+    use crate::render::{AnsiRenderer, DiscordRenderer, HtmlRenderer, PlainRenderer};
+
+    const LITIL: &str = include_str!("../../lítil.html");
+
+    #[test]
+    fn golden_discord() {
+        assert_eq!(render_html::<DiscordRenderer>(LITIL, EMPTY), include_str!("../../golden/lítil.md"));
+    }
+    #[test]
+    fn golden_plain() {
+        assert_eq!(render_html::<PlainRenderer>(LITIL, EMPTY), include_str!("../../golden/lítil.txt"));
+    }
+    #[test]
+    fn golden_ansi() {
+        assert_eq!(render_html::<AnsiRenderer>(LITIL, EMPTY), include_str!("../../golden/lítil.ansi"));
+    }
+    #[test]
+    fn golden_html() {
+        assert_eq!(render_html::<HtmlRenderer>(LITIL, EMPTY), include_str!("../../golden/lítil.html"));
+    }
+    #[test]
+    fn texts_by_class() {
+        let definitions = texts_with_class(LITIL, "_h");
+        let examples = texts_with_class(LITIL, "_d");
+
+        assert_eq!(definitions[0], "ikki stórur (í vavi), undir miðalstødd, smáur,");
+        assert_eq!(definitions[1], "eitt glas av brennivíni,");
+        assert_eq!(examples[0], "lítli fingur,");
+        assert_eq!(examples[1], "lítið hús");
+    }
+}
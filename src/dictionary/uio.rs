@@ -1,41 +1,46 @@
 use reqwest::{
     header::CONTENT_TYPE,
-    get as reqwest_get,
-    Client as ReqClient,
     RequestBuilder,
 };
-use scraper::{Html, Selector};
-use encoding_rs::mem::convert_utf8_to_latin1_lossy;
+use scraper::{ElementRef, Html, Node, Selector};
 
+use crate::http::HttpClient;
 use crate::util::Entry;
-pub async fn gm_entries(ord: &str, result_row_amount: u16) -> Result<(String, Vec<Entry>), u16> {
-    let client = ReqClient::new();
+pub async fn gm_entries(client: &HttpClient, ord: &str, result_row_amount: u16) -> Result<(String, Vec<Entry>), u16> {
+    let mut warnings = Vec::new();
 
-    let res = gm_post(client.post("http://www.edd.uio.no/perl/search/search.cgi"),
-        ord, result_row_amount)
-        .send()
-        .await
-        .unwrap();
+    let res = client.send(gm_post(client.post("http://www.edd.uio.no/perl/search/search.cgi"),
+        ord, result_row_amount, &mut warnings)).await?;
 
     if res.status().is_success() {
-        let html = Html::parse_document(&res.text().await.unwrap());
+        let html = Html::parse_document(&res.text().await?);
 
-        let entry_selector = Selector::parse(".ResRowGray td, .ResRowWhite td").unwrap();
+        let row_selector = Selector::parse(".ResRowGray, .ResRowWhite").unwrap();
+        let td_selector = Selector::parse("td").unwrap();
+        let link_selector = Selector::parse("a[href]").unwrap();
         let result_number_selector = Selector::parse(".BeneathNavigator").unwrap();
 
-        let mut iter = html.select(&entry_selector).map(|tr| tr.inner_html().trim().to_owned());
+        let mut entries = Vec::new();
 
-        let mut entries = Vec::with_capacity(iter.size_hint().0);
+        for row in html.select(&row_selector) {
+            let mut iter = row.select(&td_selector).map(|tr| tr.inner_html().trim().to_owned());
 
-        while let Some(word) = iter.next() {
-            let class = iter.next().unwrap();
-            let body = iter.next().unwrap();
+            let (Some(word), Some(class), Some(body)) = (iter.next(), iter.next(), iter.next()) else {
+                continue
+            };
 
-            entries.push(Entry::new_gm(word, class, body));
+            let mut entry = Entry::new_gm(strip_tags(&word), class, body);
+            if let Some(id) = row.select(&link_selector).find_map(|a| primary_key(a.value().attr("href").unwrap())) {
+                entry = entry.with_id(id);
+            }
+            entries.push(entry);
         }
 
         // HACK don't look at this
-        let results = html.select(&result_number_selector).next().unwrap().text().next().unwrap().to_owned();
+        let mut results = html.select(&result_number_selector).next().unwrap().text().next().unwrap().to_owned();
+        for warning in warnings.into_iter().rev() {
+            results.insert_str(0, &format!("{}\n", warning));
+        }
 
         Ok((results, entries))
     } else {
@@ -43,17 +48,75 @@ pub async fn gm_entries(ord: &str, result_row_amount: u16) -> Result<(String, Ve
     }
 }
 
-pub async fn sa_entries<'a, 'b>(ord: &'a str, result_row_amount: u16, options: SetelArkivOptions<'b>) -> Result<(String, Vec<Entry>), u16> {
-    let client = ReqClient::new();
+pub async fn gm_entry(client: &HttpClient, id: u32) -> Result<Entry, u16> {
+    let res = client.send(client.get(format!("https://www.edd.uio.no/perl/search/objectviewer.cgi?tabid=993&primarykey={}", id))).await?;
+
+    if res.status().is_success() {
+        let (oppslag, grammar, body) = parse_gm_entry(&res.text().await?).ok_or(404u16)?;
+
+        Ok(Entry::new_gm(oppslag, grammar, body).with_id(id))
+    } else {
+        Err(res.status().as_u16())
+    }
+}
+
+/// Reads the headword, grammar and body of a GM article from its page in the object viewer,
+/// or `None` if the page has no article
+fn parse_gm_entry(page: &str) -> Option<(String, String, String)> {
+    let html = Html::parse_document(page);
+
+    let oppslag_selector = Selector::parse(".oppslag").unwrap();
+    let grammar_selector = Selector::parse(".GRAMMATIKK").unwrap();
+    let body_selector = Selector::parse(".kontekst").unwrap();
+
+    let text_of = |selector: &Selector| html.select(selector).map(element_text).collect::<Vec<_>>();
+
+    let oppslag = text_of(&oppslag_selector).join(", ");
+    let grammar = text_of(&grammar_selector).join(", ");
+    // The body of a GM article is spread over several elements,
+    // these are put on each their own line
+    let body = text_of(&body_selector).join("\n");
+
+    if oppslag.is_empty() && body.is_empty() {
+        return None;
+    }
+    Some((oppslag, grammar, body))
+}
+
+/// The text of an element without its tags, keeping its line breaks
+fn element_text(element: ElementRef) -> String {
+    let mut text = String::new();
+    for node in element.descendants() {
+        match node.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) if e.name() == "br" => text.push('\n'),
+            _ => (),
+        }
+    }
+    text.trim().to_owned()
+}
+
+/// Finds the `primarykey` parameter in a link to the object viewer
+fn primary_key(href: &str) -> Option<u32> {
+    let (_, rest) = href.split_once("primarykey=")?;
+    let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+
+    rest[..end].parse().ok()
+}
+
+/// Removes html tags, such as the links around headwords in the search results
+fn strip_tags(s: &str) -> String {
+    Html::parse_fragment(s).root_element().text().collect()
+}
+
+pub async fn sa_entries<'a, 'b>(client: &HttpClient, ord: &'a str, result_row_amount: u16, options: SetelArkivOptions<'b>) -> Result<(String, Vec<Entry>), u16> {
+    let mut warnings = Vec::new();
 
-    let res = sa_post(client.post("http://www.edd.uio.no/perl/search/search.cgi"),
-        ord, result_row_amount, options)
-        .send()
-        .await
-        .unwrap();
+    let res = client.send(sa_post(client.post("http://www.edd.uio.no/perl/search/search.cgi"),
+        ord, result_row_amount, options, &mut warnings)).await?;
 
     if res.status().is_success() {
-        let html = Html::parse_document(&res.text().await.unwrap());
+        let html = Html::parse_document(&res.text().await?);
 
         let entry_selector = Selector::parse(".ResRowGray td, .ResRowWhite td").unwrap();
         let result_number_selector = Selector::parse(".BeneathNavigator").unwrap();
@@ -70,18 +133,21 @@ pub async fn sa_entries<'a, 'b>(ord: &'a str, result_row_amount: u16, options: S
         }
 
         // HACK don't look at this
-        let results = html.select(&result_number_selector).next().unwrap().text().next().unwrap().to_owned();
+        let mut results = html.select(&result_number_selector).next().unwrap().text().next().unwrap().to_owned();
+        for warning in warnings.into_iter().rev() {
+            results.insert_str(0, &format!("{}\n", warning));
+        }
 
         Ok((results, entries))
     } else {
         Err(res.status().as_u16())
     }
 }
-pub async fn sa_entry(id: u32) -> Result<(String, Option<String>), u16> {
-    let res = reqwest_get(&format!("https://www.edd.uio.no/perl/search/objectviewer.cgi?tabid=436&primarykey={}", id)).await.unwrap();
+pub async fn sa_entry(client: &HttpClient, id: u32) -> Result<(String, Option<String>), u16> {
+    let res = client.send(client.get(format!("https://www.edd.uio.no/perl/search/objectviewer.cgi?tabid=436&primarykey={}", id))).await?;
 
     if res.status().is_success() {
-        let html = Html::parse_document(&res.text().await.unwrap());
+        let html = Html::parse_document(&res.text().await?);
 
         let oppslag_selector = Selector::parse(".oppslag").unwrap();
         let grammar_selector = Selector::parse(".GRAMMATIKK").unwrap();
@@ -107,11 +173,11 @@ pub async fn sa_entry(id: u32) -> Result<(String, Option<String>), u16> {
     }
 }
 
-fn gm_post(rb: RequestBuilder, word: &str, result_row_amount: u16) -> RequestBuilder {
+fn gm_post(rb: RequestBuilder, word: &str, result_row_amount: u16, warnings: &mut Vec<String>) -> RequestBuilder {
     rb
         .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
         .body(format!("tabid=993&appid=59&C%23993.994.545%23994.995.546%23ORD={}&dosearch=++++S%F8k++++&oppsetttid=215&ResultatID=447&ResRowsNum={}",
-            uio_encode(word), result_row_amount))
+            uio_encode_warn(word, warnings), result_row_amount))
 }
 
 #[derive(Copy, Clone, Debug, Default)]
@@ -123,7 +189,7 @@ pub struct SetelArkivOptions<'a> {
     pub place_code: &'a str,
 }
 
-fn sa_post(rb: RequestBuilder, word_form: &str, result_row_amount: u16, options: SetelArkivOptions) -> RequestBuilder {
+fn sa_post(rb: RequestBuilder, word_form: &str, result_row_amount: u16, options: SetelArkivOptions, warnings: &mut Vec<String>) -> RequestBuilder {
     let SetelArkivOptions {
         registrant,
         title,
@@ -137,26 +203,153 @@ fn sa_post(rb: RequestBuilder, word_form: &str, result_row_amount: u16, options:
         .body(format!("tabid=436&appid=8&C%23436.437.235%23ORDFORM={}&C%23436.447.243%23PERSONNAMN={}&C%23436.443.239%23443.444.240%23FORFATTAR={}\
                         &C%23436.443.239%23443.444.240%23TITTEL={}&C%23436.1855.1051%231855.448.1050%23STADNAMNKODE={}&C%23436.635.339%23635.448.341%23STADNAMNKODE={}\
                         &C%23SETEL_ID=&dosearch=++++S%F8k++++&oppsettid=216&ResultatID=328&ResRowsNum={}",
-            uio_encode(word_form), uio_encode(registrant), uio_encode(author), uio_encode(title), uio_encode(area_code), uio_encode(place_code), result_row_amount))
+            uio_encode_warn(word_form, warnings), uio_encode_warn(registrant, warnings), uio_encode_warn(author, warnings),
+            uio_encode_warn(title, warnings), uio_encode_warn(area_code, warnings), uio_encode_warn(place_code, warnings), result_row_amount))
 }
 
-fn uio_encode(s: &str) -> String {
-    let mut buf_bytes =  vec![0; s.len()];
+/// Encodes the string for use in a form for the UiO search engine, which expects Latin-1.
+///
+/// Characters outside of Latin-1 are written as the entities the databases themselves use,
+/// or otherwise transliterated to something close in Latin-1.
+/// If anything had to be transliterated or left out, the query as it will actually be searched for
+/// is returned as well.
+fn uio_encode(s: &str) -> (String, Option<String>) {
+    let mut encoded = String::with_capacity(s.len());
+    let mut searched_for = String::with_capacity(s.len());
+    let mut changed = false;
+
+    for c in s.chars() {
+        if let Ok(byte) = u8::try_from(c) {
+            encode_byte(&mut encoded, byte);
+            searched_for.push(c);
+        } else if let Some(entity) = entity(c) {
+            entity.bytes().for_each(|b| encode_byte(&mut encoded, b));
+            searched_for.push(c);
+        } else {
+            changed = true;
+
+            let replacement = transliterate(c).unwrap_or("");
+            // Transliterations are all within Latin-1
+            replacement.chars().for_each(|c| encode_byte(&mut encoded, c as u8));
+            searched_for.push_str(replacement);
+        }
+    }
 
-    convert_utf8_to_latin1_lossy(s.as_bytes(), &mut buf_bytes);
+    (encoded, if changed { Some(searched_for) } else { None })
+}
 
-    let mut encoded = String::with_capacity(buf_bytes.len());
+/// Same as [`uio_encode`] but puts a message in `warnings` if the query had to be changed
+fn uio_encode_warn(s: &str, warnings: &mut Vec<String>) -> String {
+    let (encoded, changed) = uio_encode(s);
 
-    for byte in buf_bytes {
-        match byte {
-            0x00 => (),
-            0x01..=0x1f | 0x21..=0x2c | 0x3a..=0x40 | 0x5b..=0x60 | 0x7b..=0xff => {
-                encoded.push_str(&format!("%{:02X}", byte));
-            }
-            0x20 => encoded.push('+'),
-            0x2d..=0x39 | 0x41..=0x5a | 0x61..=0x7a => encoded.push(byte as char),
-        }
+    if let Some(changed) = changed {
+        warnings.push(format!("Leitaði eftir _{}_ í staðin fyri _{}_", changed, s));
     }
 
     encoded
-}
\ No newline at end of file
+}
+
+fn encode_byte(encoded: &mut String, byte: u8) {
+    match byte {
+        0x00..=0x1f | 0x21..=0x2c | 0x3a..=0x40 | 0x5b..=0x60 | 0x7b..=0xff => {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+        0x20 => encoded.push('+'),
+        0x2d..=0x39 | 0x41..=0x5a | 0x61..=0x7a => encoded.push(byte as char),
+    }
+}
+
+/// The entities used in the databases for characters outside of Latin-1
+fn entity(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ē' => "&emacr;",
+        'œ' => "&oelig;",
+        'Ǫ' => "&Oogon;",
+        'ǫ' => "&oogon;",
+        'ō' => "&omacr;",
+        '♯' => "&kryss;",
+        '†' => "&dagger;",
+        _ => return None,
+    })
+}
+
+/// Closest Latin-1 spelling of characters that the databases have no entity for
+fn transliterate(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ā' | 'ă' | 'ą' => "a",
+        'Ā' | 'Ă' | 'Ą' => "A",
+        'ǣ' | 'ǽ' => "æ",
+        'Ǣ' | 'Ǽ' => "Æ",
+        'ć' | 'č' => "c",
+        'Ć' | 'Č' => "C",
+        'đ' => "d",
+        'Đ' => "D",
+        'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'Ī' | 'Ĭ' | 'Į' | 'İ' => "I",
+        'ł' => "l",
+        'Ł' => "L",
+        'ń' | 'ň' | 'ŋ' => "n",
+        'Ń' | 'Ň' | 'Ŋ' => "N",
+        'ŏ' | 'ő' => "o",
+        'Ō' | 'Ŏ' | 'Ő' => "O",
+        'ǭ' | 'ǿ' => "ø",
+        'Ǭ' | 'Ǿ' | 'Œ' => "Ø",
+        'ś' | 'š' => "s",
+        'Ś' | 'Š' => "S",
+        'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => "U",
+        'ȳ' => "y",
+        'Ȳ' => "Y",
+        'ž' | 'ź' | 'ż' => "z",
+        'Ž' | 'Ź' | 'Ż' => "Z",
+        '‘' | '’' => "'",
+        '“' | '”' | '„' => "\"",
+        '–' | '—' => "-",
+        '…' => "...",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_gm_entry, uio_encode};
+
+    /// A GM article as shown by the object viewer
+    const GM: &str = include_str!("../../gm.html");
+
+    #[test]
+    fn test_parse_gm_entry() {
+        let (oppslag, grammar, body) = parse_gm_entry(GM).unwrap();
+
+        assert_eq!(oppslag, "hestur");
+        assert_eq!(grammar, "m. & hk.");
+        assert_eq!(body, "1) hest; ríða hesti\nríde på en hest &kryss;\n2) sbr. føli");
+        assert_eq!(parse_gm_entry("<html><body><p>Ingen treff</p></body></html>"), None);
+    }
+
+    #[test]
+    fn test_uio_encode() {
+        assert_eq!(uio_encode("hest"), ("hest".to_owned(), None));
+        assert_eq!(uio_encode("ein hestur"), ("ein+hestur".to_owned(), None));
+        assert_eq!(uio_encode("søk"), ("s%F8k".to_owned(), None));
+        assert_eq!(uio_encode("ÆØÅ"), ("%C6%D8%C5".to_owned(), None));
+        assert_eq!(uio_encode("a&b"), ("a%26b".to_owned(), None));
+    }
+
+    #[test]
+    fn test_uio_encode_entities() {
+        assert_eq!(uio_encode("hǫnd"), ("h%26oogon%3Bnd".to_owned(), None));
+        assert_eq!(uio_encode("kōmr"), ("k%26omacr%3Bmr".to_owned(), None));
+        assert_eq!(uio_encode("Ǫ"), ("%26Oogon%3B".to_owned(), None));
+    }
+
+    #[test]
+    fn test_uio_encode_transliterated() {
+        assert_eq!(uio_encode("vāgr"), ("vagr".to_owned(), Some("vagr".to_owned())));
+        assert_eq!(uio_encode("sǿr"), ("s%F8r".to_owned(), Some("sør".to_owned())));
+        assert_eq!(uio_encode("hest🐴"), ("hest".to_owned(), Some("hest".to_owned())));
+        assert_eq!(uio_encode("ǭ ā"), ("%F8+a".to_owned(), Some("ø a".to_owned())));
+    }
+}
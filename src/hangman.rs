@@ -0,0 +1,284 @@
+//! Guessing a word letter by letter, with hints from its entry on Sprotin.
+
+use std::collections::hash_map::{Entry, HashMap};
+
+use rand::rng;
+use rand::seq::SliceRandom;
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::TypeMapKey;
+
+use crate::dictionary::sprotin::{search, SprotinWord};
+use crate::http::HttpClient;
+use crate::wordgame::alphabet;
+use crate::wordlist::WordList;
+
+/// How many wrong letters can be guessed before the game is lost
+pub const MAX_MISSES: usize = 6;
+/// After how many wrong letters each of the hints is given
+const HINT_MISSES: [usize; 3] = [1, 3, 5];
+const MIN_LETTERS: usize = 4;
+/// How many words are looked up on Sprotin before giving up finding one with an entry
+const MAX_LOOKUPS: usize = 5;
+
+fn is_letter(c: char) -> bool {
+    alphabet().any(|a| a == c)
+}
+
+/// The letter if the message is just one letter of the alphabet
+pub fn single_letter(s: &str) -> Option<char> {
+    let mut chars = s.trim().chars().flat_map(char::to_lowercase);
+    let c = chars.next().filter(|&c| is_letter(c))?;
+
+    chars.next().is_none().then_some(c)
+}
+
+/// Picks a random headword from the word list that has an entry in FØ-FØ
+pub async fn pick_word(client: &HttpClient, word_list: &WordList) -> Result<Option<(String, SprotinWord)>, u16> {
+    let mut candidates: Vec<String> = word_list.headwords().into_iter()
+        .filter(|w| w.chars().count() >= MIN_LETTERS && w.chars().all(is_letter))
+        .collect();
+    candidates.shuffle(&mut rng());
+
+    for word in candidates.into_iter().take(MAX_LOOKUPS) {
+        let response = search(client, 1, 1, &word, false, false).await?;
+
+        if let Some(entry) = response.words.into_iter().find(|w| w.search_word == word) {
+            return Ok(Some((word, entry)));
+        }
+    }
+    Ok(None)
+}
+
+/// Hides the word and its forms in the text, along with words that contain it
+fn mask(text: &str, word: &str, forms: &[String]) -> String {
+    let mut masked = String::with_capacity(text.len());
+    let mut run = String::new();
+
+    let end_run = |run: &mut String, masked: &mut String| {
+        let lower = run.to_lowercase();
+        if forms.contains(&lower) || lower.contains(word) {
+            masked.push('…');
+        } else {
+            masked.push_str(run);
+        }
+        run.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_alphabetic() {
+            run.push(c);
+        } else {
+            end_run(&mut run, &mut masked);
+            masked.push(c);
+        }
+    }
+    end_run(&mut run, &mut masked);
+
+    masked
+}
+
+/// The hints that can be given for the word, in the order they are given
+fn hints(word: &str, entry: &SprotinWord) -> Vec<String> {
+    let forms: Vec<String> = entry.inflected_form.iter()
+        .flat_map(|f| f.split('/'))
+        .map(|f| f.trim().to_lowercase())
+        .filter(|f| !f.is_empty())
+        .collect();
+
+    [
+        entry.inflex_cats().map(|c| format!("Word class: {}", c)),
+        entry.definitions().first().map(|d| format!("Meaning: {}", mask(d, word, &forms))),
+        entry.examples().first().map(|e| format!("Example: _{}_", mask(e, word, &forms))),
+    ].into_iter().flatten().collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guess {
+    Hit,
+    Miss,
+    AlreadyGuessed,
+    /// Not a letter of the alphabet, so not counted
+    NotALetter,
+}
+
+pub struct Hangman {
+    word: Vec<char>,
+    /// The entry of the word, shown when the game is over
+    pub entry: SprotinWord,
+    guessed: Vec<char>,
+    misses: usize,
+    hints: Vec<String>,
+    /// The message showing the game, which is edited as letters are guessed
+    pub message: Message,
+}
+
+impl Hangman {
+    pub fn new(word: &str, entry: SprotinWord) -> Self {
+        Hangman {
+            word: word.chars().collect(),
+            hints: hints(word, &entry),
+            entry,
+            guessed: Vec::new(),
+            misses: 0,
+            message: Message::default(),
+        }
+    }
+    pub fn word(&self) -> String {
+        self.word.iter().collect()
+    }
+    pub fn guess(&mut self, c: char) -> Guess {
+        if !is_letter(c) {
+            return Guess::NotALetter;
+        }
+        if self.guessed.contains(&c) {
+            return Guess::AlreadyGuessed;
+        }
+        self.guessed.push(c);
+
+        if self.word.contains(&c) {
+            Guess::Hit
+        } else {
+            self.misses += 1;
+            Guess::Miss
+        }
+    }
+    pub fn is_won(&self) -> bool {
+        self.word.iter().all(|c| self.guessed.contains(c))
+    }
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.misses >= MAX_MISSES
+    }
+    /// The word with the letters that haven't been guessed left out
+    pub fn pattern(&self) -> String {
+        let letters: Vec<String> = self.word.iter()
+            .map(|&c| if self.guessed.contains(&c) { c.to_uppercase().to_string() } else { "_".to_owned() })
+            .collect();
+        letters.join(" ")
+    }
+    /// The hints that have been given so far
+    pub fn hints(&self) -> &[String] {
+        let given = HINT_MISSES.iter().filter(|&&m| self.misses >= m).count();
+        &self.hints[..given.min(self.hints.len())]
+    }
+    /// Everything about the game so far, for its message
+    pub fn status(&self) -> String {
+        let misses: Vec<String> = self.guessed.iter()
+            .filter(|c| !self.word.contains(c))
+            .map(|c| c.to_uppercase().to_string())
+            .collect();
+
+        let mut s = format!("`{}`\n", self.pattern());
+        if !misses.is_empty() {
+            s.push_str(&format!("Wrong letters: {}\n", misses.join(" ")));
+        }
+        s.push_str(&format!("{} wrong guesses left\n", MAX_MISSES - self.misses));
+        for hint in self.hints() {
+            s.push_str(&format!("{}\n", hint));
+        }
+        s.push_str("Guess by sending a letter on its own");
+        s
+    }
+}
+
+#[derive(Default)]
+/// The games of hangman being played, one for each channel
+pub struct Hangmans {
+    games: HashMap<ChannelId, Hangman>,
+}
+
+impl TypeMapKey for Hangmans {
+    type Value = Self;
+}
+
+impl Hangmans {
+    pub fn contains(&self, channel_id: ChannelId) -> bool {
+        self.games.contains_key(&channel_id)
+    }
+    pub fn get_mut(&mut self, channel_id: ChannelId) -> Option<&mut Hangman> {
+        self.games.get_mut(&channel_id)
+    }
+    /// Starts the game in the channel of its message, unless there is a game there already
+    pub fn start(&mut self, game: Hangman) -> bool {
+        match self.games.entry(game.message.channel_id) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(v) => {
+                v.insert(game);
+                true
+            }
+        }
+    }
+    pub fn remove(&mut self, channel_id: ChannelId) -> Option<Hangman> {
+        self.games.remove(&channel_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mask, single_letter, Guess, Hangman, MAX_MISSES};
+    use crate::dictionary::sprotin::SprotinWord;
+
+    fn entry() -> SprotinWord {
+        serde_json::from_value(serde_json::json!({
+            "Id": 1,
+            "SearchWord": "lítil",
+            "DisplayWord": "lítil",
+            "InflexCats": "lo",
+            "InflectedForm": ["lítil", "lítla/lítlan", "lítið"],
+            "Explanation": "<span class=\"_h\">ikki stórur, </span><span class=\"_d\">lítið hús</span>",
+            "Index": 1,
+            "Date": "2024-05-15 12:00:00",
+            "Groups": [],
+        })).unwrap()
+    }
+
+    #[test]
+    fn single_letters() {
+        assert_eq!(single_letter(" Ð "), Some('ð'));
+        assert_eq!(single_letter("á"), Some('á'));
+        assert_eq!(single_letter("ab"), None);
+        assert_eq!(single_letter("c"), None);
+        assert_eq!(single_letter("?"), None);
+    }
+    #[test]
+    fn masks_forms() {
+        let forms = ["lítil".to_owned(), "lítið".to_owned(), "lítli".to_owned()];
+
+        assert_eq!(mask("lítið hús", "lítil", &forms), "… hús");
+        assert_eq!(mask("Lítli fingur, lítilsháttar", "lítil", &forms), "… fingur, …");
+        assert_eq!(mask("ein stórur hestur", "lítil", &forms), "ein stórur hestur");
+    }
+    #[test]
+    fn hints_with_misses() {
+        let mut game = Hangman::new("lítil", entry());
+
+        assert_eq!(game.guess('l'), Guess::Hit);
+        assert_eq!(game.guess('l'), Guess::AlreadyGuessed);
+        assert_eq!(game.pattern(), "L _ _ _ L");
+        assert!(game.hints().is_empty());
+
+        assert_eq!(game.guess('a'), Guess::Miss);
+        assert_eq!(game.hints(), ["Word class: _lo_"]);
+        game.guess('e');
+        game.guess('o');
+        assert_eq!(game.hints()[1], "Meaning: ikki stórur");
+        game.guess('u');
+        game.guess('s');
+        assert_eq!(game.hints()[2], "Example: _… hús_");
+
+        assert!(!game.is_over());
+        game.guess('r');
+        assert!(game.is_over() && !game.is_won());
+        assert_eq!(MAX_MISSES, 6);
+    }
+    #[test]
+    fn won_when_all_letters_found() {
+        let mut game = Hangman::new("lítil", entry());
+        for c in ['l', 'í', 't', 'i'] {
+            game.guess(c);
+        }
+
+        assert!(game.is_won());
+        assert_eq!(game.pattern(), "L Í T I L");
+    }
+}
@@ -0,0 +1,139 @@
+//! The HTTP client shared by all the dictionary backends.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use reqwest::{Client as ReqClient, Error, IntoUrl, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serenity::prelude::TypeMapKey;
+use tokio::{sync::{OwnedSemaphorePermit, Semaphore}, time::sleep};
+
+const USER_AGENT: &str = concat!("ordabottur/", env!("CARGO_PKG_VERSION"), " (+https://github.com/LFalch/ordabottur)");
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const READ_TIMEOUT: Duration = Duration::from_secs(15);
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many times a request is tried in total before giving up on a 5xx response or a failed connection
+const MAX_ATTEMPTS: u32 = 3;
+/// The wait before the first retry, doubled for each following one
+const BACKOFF: Duration = Duration::from_millis(500);
+/// How many requests are allowed to be in flight to the same host at once
+const MAX_REQUESTS_PER_HOST: usize = 2;
+
+#[derive(Debug, Clone)]
+/// A configured client with timeouts, retries and a limit on concurrent requests per host
+///
+/// Cloning is cheap and all clones share the same connection pool and limits.
+pub struct HttpClient {
+    client: ReqClient,
+    hosts: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+}
+
+impl TypeMapKey for HttpClient {
+    type Value = Self;
+}
+
+impl HttpClient {
+    /// Makes a new client
+    ///
+    /// There is no `Default`, since only the client made at startup should be used,
+    /// so that the limits are shared by all requests.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        let client = ReqClient::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(READ_TIMEOUT)
+            .timeout(TIMEOUT)
+            .build()
+            .expect("Could not make HTTP client");
+
+        HttpClient {
+            client,
+            hosts: Arc::default(),
+        }
+    }
+
+    #[inline]
+    /// Starts building a GET request to be sent with [`HttpClient::send`]
+    pub fn get<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client.get(url)
+    }
+    #[inline]
+    /// Starts building a POST request to be sent with [`HttpClient::send`]
+    pub fn post<U: IntoUrl>(&self, url: U) -> RequestBuilder {
+        self.client.post(url)
+    }
+
+    fn host_limit(&self, host: &str) -> Arc<Semaphore> {
+        self.hosts
+            .lock()
+            .unwrap()
+            .entry(host.to_owned())
+            .or_insert_with(|| Arc::new(Semaphore::new(MAX_REQUESTS_PER_HOST)))
+            .clone()
+    }
+
+    /// Sends the request, retrying with backoff on server errors and failed connections.
+    ///
+    /// Responses that aren't server errors are returned as is, whether they are successful or not,
+    /// and count towards the limit of their host until they are read or dropped.
+    /// If no response could be gotten, the error is a status code from [`error_status`].
+    pub async fn send(&self, rb: RequestBuilder) -> Result<HttpResponse, u16> {
+        let mut request = rb.build().map_err(|_| 400u16)?;
+        let limit = self.host_limit(request.url().host_str().unwrap_or(""));
+        let permit = limit.acquire_owned().await.unwrap();
+        let response = |response| HttpResponse { response, _permit: permit };
+
+        let mut backoff = BACKOFF;
+        let mut attempt = 1;
+
+        loop {
+            // Requests with streamed bodies can't be cloned and are only tried once
+            let retry = if attempt < MAX_ATTEMPTS { request.try_clone() } else { None };
+
+            match (self.client.execute(request).await, retry) {
+                (Ok(res), None) => return Ok(response(res)),
+                (Ok(res), Some(_)) if !res.status().is_server_error() => return Ok(response(res)),
+                (Err(e), None) => return Err(error_status(&e)),
+                (_, Some(retry)) => request = retry,
+            }
+
+            sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+/// A status code describing why a request or reading its body failed:
+/// 504 for timeouts and 503 for everything else
+fn error_status(e: &Error) -> u16 {
+    if e.is_timeout() { 504 } else { 503 }
+}
+
+#[derive(Debug)]
+/// A response whose body hasn't been read yet, holding on to its place in the limit of its host
+pub struct HttpResponse {
+    response: Response,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl HttpResponse {
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.response.status()
+    }
+    /// Reads the body as text, which can also time out
+    pub async fn text(self) -> Result<String, u16> {
+        self.response.text().await.map_err(|e| error_status(&e))
+    }
+    /// Reads the body as JSON, which can also time out
+    pub async fn json<T: DeserializeOwned>(self) -> Result<T, u16> {
+        self.response.json().await.map_err(|e| error_status(&e))
+    }
+}
@@ -0,0 +1,176 @@
+//! Forms of words that Sprotin doesn't list, derived from the forms it does list.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordClass {
+    Verb,
+    Noun,
+    Adjective,
+    Other,
+}
+
+impl WordClass {
+    /// Guesses the word class from the number of inflected forms, like [`SprotinWord::inflection_table`]
+    ///
+    /// [`SprotinWord::inflection_table`]: crate::dictionary::sprotin::SprotinWord::inflection_table
+    pub fn from_forms<S>(forms: &[S]) -> Self {
+        match forms.len() {
+            6 => WordClass::Verb,
+            16 => WordClass::Noun,
+            24 => WordClass::Adjective,
+            _ => WordClass::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Derivation {
+    /// Verbs ending in -st, e.g. _kallast_ from _kalla_
+    Mediopassive,
+    /// Adjectives ending in -ari, e.g. _ríkari_ from _ríkur_
+    Comparative,
+    /// Adjectives ending in -ast(ur), e.g. _ríkastur_ from _ríkur_
+    Superlative,
+}
+
+impl Display for Derivation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Derivation::Mediopassive => write!(f, "mediopassive"),
+            Derivation::Comparative => write!(f, "comparative"),
+            Derivation::Superlative => write!(f, "superlative"),
+        }
+    }
+}
+
+impl FromStr for Derivation {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mediopassive" => Ok(Derivation::Mediopassive),
+            "comparative" => Ok(Derivation::Comparative),
+            "superlative" => Ok(Derivation::Superlative),
+            _ => Err(()),
+        }
+    }
+}
+
+const COMPARATIVE_ENDINGS: [&str; 1] = ["ari"];
+const SUPERLATIVE_ENDINGS: [&str; 4] = ["astur", "asta", "asti", "ast"];
+
+/// The stem of an adjective in the masculine nominative singular, e.g. _rík_ of _ríkur_
+fn adjective_stem(m_nom_sg: &str) -> &str {
+    ["ur", "ir", "in"].iter()
+        .find_map(|ending| m_nom_sg.strip_suffix(ending))
+        .unwrap_or(m_nom_sg)
+}
+
+/// Forms derived from the inflected forms of a word in the order Sprotin gives them
+///
+/// The derivations follow the regular patterns, so irregular words can get forms that don't exist
+/// and miss forms that do. Forms Sprotin lists with alternatives like `a/b` only use the first.
+pub fn derived_forms<S: AsRef<str>>(forms: &[S]) -> Vec<(String, Derivation)> {
+    let first = |i: usize| forms[i].as_ref().split('/').next().unwrap_or("").trim();
+
+    let mut derived = Vec::new();
+    match WordClass::from_forms(forms) {
+        WordClass::Verb => {
+            let (infinitive, present_3p, past_sg, past_pl) = (first(0), first(1), first(2), first(3));
+
+            for form in [infinitive, past_sg, past_pl] {
+                derived.push((format!("{}st", form), Derivation::Mediopassive));
+            }
+            if let Some(stem) = present_3p.strip_suffix('r') {
+                derived.push((format!("{}st", stem), Derivation::Mediopassive));
+            }
+        }
+        WordClass::Adjective => {
+            let stem = adjective_stem(first(0));
+
+            for ending in COMPARATIVE_ENDINGS {
+                derived.push((format!("{}{}", stem, ending), Derivation::Comparative));
+            }
+            for ending in SUPERLATIVE_ENDINGS {
+                derived.push((format!("{}{}", stem, ending), Derivation::Superlative));
+            }
+        }
+        WordClass::Noun | WordClass::Other => (),
+    }
+
+    let mut unique = Vec::with_capacity(derived.len());
+    for d in derived {
+        if d.0.chars().count() > 2 && !unique.contains(&d) {
+            unique.push(d);
+        }
+    }
+    unique
+}
+
+/// Words the given word could be derived from, to be looked up when the word itself isn't found
+pub fn base_candidates(word: &str) -> Vec<String> {
+    let mut candidates = Vec::new();
+
+    if let Some(base) = word.strip_suffix("st") {
+        candidates.push(base.to_owned());
+    }
+    for ending in COMPARATIVE_ENDINGS.iter().chain(&SUPERLATIVE_ENDINGS) {
+        if let Some(stem) = word.strip_suffix(ending) {
+            candidates.push(format!("{}ur", stem));
+        }
+    }
+
+    candidates.retain(|c| c.chars().count() >= 2 && c != word);
+    candidates.dedup();
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{base_candidates, derived_forms, Derivation, WordClass};
+
+    const KALLA: [&str; 6] = ["kalla", "kallar", "kallaði", "kallaðu", "kallað", "kallaður"];
+
+    fn rikur() -> Vec<String> {
+        let mut forms = vec!["ríkur".to_owned()];
+        forms.resize(24, "ríkt".to_owned());
+        forms
+    }
+
+    #[test]
+    fn word_classes() {
+        assert_eq!(WordClass::from_forms(&KALLA), WordClass::Verb);
+        assert_eq!(WordClass::from_forms(&rikur()), WordClass::Adjective);
+        assert_eq!(WordClass::from_forms(&["hús"; 16]), WordClass::Noun);
+        assert_eq!(WordClass::from_forms::<&str>(&[]), WordClass::Other);
+    }
+    #[test]
+    fn mediopassive() {
+        let derived = derived_forms(&KALLA);
+
+        assert!(derived.contains(&("kallast".to_owned(), Derivation::Mediopassive)));
+        assert!(derived.contains(&("kallaðist".to_owned(), Derivation::Mediopassive)));
+        assert!(derived.contains(&("kallaðust".to_owned(), Derivation::Mediopassive)));
+        assert_eq!(derived.iter().filter(|(f, _)| f == "kallast").count(), 1);
+    }
+    #[test]
+    fn comparison() {
+        let derived = derived_forms(&rikur());
+
+        assert!(derived.contains(&("ríkari".to_owned(), Derivation::Comparative)));
+        assert!(derived.contains(&("ríkastur".to_owned(), Derivation::Superlative)));
+        assert!(derived.contains(&("ríkast".to_owned(), Derivation::Superlative)));
+    }
+    #[test]
+    fn no_derivations_for_nouns() {
+        assert!(derived_forms(&["hús"; 16]).is_empty());
+    }
+    #[test]
+    fn bases() {
+        assert_eq!(base_candidates("kallast"), vec!["kalla", "kallur"]);
+        assert_eq!(base_candidates("ríkari"), vec!["ríkur"]);
+        assert_eq!(base_candidates("ríkastur"), vec!["ríkur"]);
+        assert!(base_candidates("hús").is_empty());
+    }
+}
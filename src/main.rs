@@ -2,11 +2,13 @@
 
 use std::{
     env,
-    collections::HashSet,
-    str::FromStr
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
 };
 
-use serenity::{all::{standard::Configuration, ActivityData, CreateAllowedMentions, CreateEmbed, CreateMessage, EditMessage}, async_trait, model::{prelude::Member, user::User}, prelude::*, utils::ContentSafeOptions};
+use serenity::{all::{standard::Configuration, ActivityData, CreateAllowedMentions, CreateAttachment, CreateEmbed, CreateMessage, EditAttachments, EditMessage}, async_trait, model::{prelude::Member, user::User}, prelude::*, utils::ContentSafeOptions};
 use serenity::framework::standard::{
     Args,
     CommandResult,
@@ -27,6 +29,7 @@ use serenity::model::{
 };
 
 use numbers_to_words::to_faroese_words;
+use tokio::time::sleep;
 
 const FALCH: UserId = UserId::new(165_877_785_544_491_008);
 
@@ -36,17 +39,71 @@ const NYGGIR_LIMIR: ChannelId = ChannelId::new(432971266312503301);
 
 const PREFIX: &str = "]";
 
+pub mod anagram;
+pub mod boardimage;
 pub mod dictionary {
     pub mod uio;
     pub mod sprotin;
 }
+pub mod hangman;
+pub mod http;
+pub mod inflection;
+pub mod pager;
+pub mod render;
+pub mod settings;
+pub mod stats;
 pub mod util;
 pub mod wordgame;
+pub mod wordle;
+pub mod wordlist;
 
-use dictionary::uio::{sa_entries, sa_entry, gm_entries, SetelArkivOptions};
+use anagram::{AnagramGame, AnagramGames, SharedAnagram};
+use boardimage::BoardPainter;
+use dictionary::uio::{sa_entries, sa_entry, gm_entries, gm_entry, SetelArkivOptions};
 use dictionary::sprotin::search as fo_search;
-use util::MsgBunchBuilder;
-use wordgame::{WordGameState, GuessError};
+use hangman::{Guess, Hangman, Hangmans};
+use http::HttpClient;
+use pager::{send_bunch as send_bunch_with_threshold, send_embeds};
+use settings::{GuildSettings, Settings};
+use stats::{DailyRecord, GameRecord, Period, WordGameStats};
+use util::{EmbedBunch, MsgBunch, MsgBunchBuilder};
+use wordgame::{WordGames, WordGameState, SharedGame, GuessError, Bonus, Generation, Mode, Rule, Scoring, Seed, Table};
+use wordle::{Tile, Wordle, WordleError, Wordles};
+use wordlist::WordList;
+
+async fn http_client(ctx: &Context) -> HttpClient {
+    ctx.data.read().await.get::<HttpClient>().cloned().expect("The HTTP client is made at startup")
+}
+async fn word_list(ctx: &Context) -> WordList {
+    ctx.data.read().await.get::<WordList>().cloned().unwrap_or_default()
+}
+/// The board as a picture with the path highlighted, if it can be painted
+async fn board_picture(ctx: &Context, table: &Table, path: &[usize]) -> Option<CreateAttachment> {
+    let painter = ctx.data.read().await.get::<BoardPainter>().cloned().unwrap_or_default();
+    painter.paint(table, path).map(|png| CreateAttachment::bytes(png, "board.png"))
+}
+async fn word_game(ctx: &Context, channel_id: ChannelId) -> Option<SharedGame> {
+    ctx.data.read().await.get::<WordGames>().and_then(|games| games.get(channel_id))
+}
+
+async fn anagram_game(ctx: &Context, channel_id: ChannelId) -> Option<SharedAnagram> {
+    ctx.data.read().await.get::<AnagramGames>().and_then(|games| games.get(channel_id))
+}
+
+async fn guild_settings(ctx: &Context, guild_id: Option<GuildId>) -> GuildSettings {
+    ctx.data.read().await.get::<Settings>().map(|s| s.guild(guild_id)).unwrap_or_default()
+}
+
+/// Sends the bunch in reply to `msg` either paged or as a file depending on the settings of the guild
+async fn send_bunch(ctx: &Context, msg: &Message, bunch: MsgBunch) -> serenity::Result<Message> {
+    send_bunch_to(ctx, msg.guild_id, msg.channel_id, msg.author.id, bunch).await
+}
+/// Sends the bunch to the channel with pages that `user` can turn, or as a file depending on the settings of the guild
+async fn send_bunch_to(ctx: &Context, guild_id: Option<GuildId>, channel_id: ChannelId, user: UserId, bunch: MsgBunch) -> serenity::Result<Message> {
+    let threshold = guild_settings(ctx, guild_id).await.attachment_threshold;
+
+    send_bunch_with_threshold(ctx, channel_id, user, bunch, threshold).await
+}
 
 #[command]
 #[description = "Set the status of the bot to be playing the set game"]
@@ -60,7 +117,7 @@ async fn setgame(ctx: &Context, _msg: &Message, args: Args) -> CommandResult {
 #[command]
 #[description = "Søk i grunnmanuskriptet"]
 async fn gm(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
-    match gm_entries(args.message(), 10).await {
+    match gm_entries(&http_client(ctx).await, args.message(), 10).await {
         Ok((results_msg, entries)) => {
             let mut mmb = MsgBunchBuilder::new();
 
@@ -69,9 +126,29 @@ async fn gm(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
                 .add_string("\n")
                 .entries(entries);
 
-            for msg_body in mmb.build().messages {
-                msg.channel_id.say(&ctx, msg_body).await?;
-            }
+            send_bunch(ctx, msg, mmb.build()).await?;
+        }
+        Err(e) => {
+            msg.channel_id.say(&ctx, &format!("Eg fekk tíverri {}", e)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[description = "Sjå eit heilt oppslag frå Grunnmanuskriptet"]
+#[usage = "<id>"]
+async fn gmi(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let id = args.single()?;
+
+    match gm_entry(&http_client(ctx).await, id).await {
+        Ok(entry) => {
+            let mut mmb = MsgBunchBuilder::new();
+
+            mmb.entries(vec![entry]);
+
+            send_bunch(ctx, msg, mmb.build()).await?;
         }
         Err(e) => {
             msg.channel_id.say(&ctx, &format!("Eg fekk tíverri {}", e)).await?;
@@ -114,7 +191,7 @@ async fn sa(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
         }
     }
 
-    match sa_entries(oppslag, 35, options).await {
+    match sa_entries(&http_client(ctx).await, oppslag, 35, options).await {
         Ok((results_msg, entries)) => {
             let mut mmb = MsgBunchBuilder::new();
 
@@ -124,9 +201,7 @@ async fn sa(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
                 .entries(entries);
             let msg_bunch = mmb.build();
 
-            for msg_body in msg_bunch.messages {
-                msg.channel_id.say(&ctx, msg_body).await?;
-            }
+            send_bunch(ctx, msg, msg_bunch).await?;
         }
         Err(e) => {
             msg.channel_id.say(&ctx, &format!("Eg fekk tíverri {}", e)).await?;
@@ -140,7 +215,7 @@ async fn sa(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
 async fn sai(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let id = args.single()?;
 
-    match sa_entry(id).await {
+    match sa_entry(&http_client(ctx).await, id).await {
         Ok((oppslag, img_src)) => {
             let mut cm = CreateMessage::new().content(oppslag);
             if let Some(img_src) = img_src {
@@ -217,18 +292,16 @@ impl FromStr for DictionaryId {
 async fn sprotin(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
     let dict = args.single::<DictionaryId>().unwrap_or(DictionaryId(1));
 
-    match fo_search(dict.0, 1, &args.single_quoted::<String>()?, false, false).await {
+    match fo_search(&http_client(ctx).await, dict.0, 1, &args.single_quoted::<String>()?, false, false).await {
         Ok(result) => {
-            let msg_bunch;
-
-            if let Ok(id) = args.single() {
-                msg_bunch = result.word(id).unwrap_or_else(|| result.summary())
+            // A single entry is shown in embeds, which have room for more of it in each message
+            let word_nr = args.single().ok();
+            if let Some((embeds, text)) = word_nr.and_then(|id| Some((result.word::<EmbedBunch>(id)?, result.word::<MsgBunch>(id)?))) {
+                let threshold = guild_settings(ctx, msg.guild_id).await.attachment_threshold;
+                send_embeds(ctx, msg.channel_id, msg.author.id, embeds, text, threshold).await?;
             } else {
-                msg_bunch = result.summary()
-            }
-
-            for msg_body in msg_bunch.messages {
-                msg.channel_id.say(&ctx, msg_body).await?;
+                let msg_bunch: MsgBunch = result.summary();
+                send_bunch(ctx, msg, msg_bunch).await?;
             }
         }
         Err(e) => {
@@ -302,50 +375,803 @@ async fn num(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
     Ok(())
 }
 
+/// Says how a word game is scored, shown when it starts and on the scoreboards
+fn scoring_line(scoring: Scoring, bonus: Bonus) -> String {
+    match bonus {
+        Bonus::None => format!("Scoring: {}", scoring),
+        bonus => format!("Scoring: {}, {}", scoring, bonus),
+    }
+}
+
 #[command]
-#[description = "Start a word game!"]
+#[description = "Start a word game! With `boggle` words must follow neighbouring letters. The board can be 4×4, 5×5 or 6×6 and is made with letter dice or with `letters` weighted by how common they are. A board can be played again with its seed, and `daily` plays the daily challenge, which is the same for everyone and can be played once a day. Words are scored by length squared, by `brackets` of length or by the `rarity` of their letters, and with `first` or `unique` more players can find the same word for a bonus to the first finder or to words only one player found."]
 #[aliases(wordgame, orðaspæl)]
-#[max_args(0)]
-async fn wg(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
-    if ctx.data.read().await.get::<wordgame::WordGameState>().is_some() {
-        // If a game is already happening, don't replace it
+#[usage = "[boggle] [dice|letters] [4|5|6] [squared|brackets|rarity] [first|unique] | <seed> | daily"]
+#[max_args(5)]
+async fn wg(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    if ctx.data.read().await.get::<WordGames>().is_some_and(|games| games.contains(msg.channel_id))
+        || anagram_game(ctx, msg.channel_id).await.is_some() {
+        // If a game is already happening here, don't replace it
+        msg.react(ctx, '🔂').await?;
+        return Ok(())
+    }
+
+    let mut mode = Mode::default();
+    let mut generation = Generation::default();
+    let mut width = wordgame::TABLE_WIDTHS[0];
+    let mut scoring = Scoring::default();
+    let mut bonus = Bonus::default();
+    let mut seed = None;
+    let mut daily = None;
+    for arg in args.raw() {
+        if let Ok(m) = arg.parse() {
+            mode = m;
+        } else if let Ok(g) = arg.parse() {
+            generation = g;
+        } else if let Some(w) = parse_width(arg) {
+            width = w;
+        } else if let Ok(s) = arg.parse() {
+            scoring = s;
+        } else if let Ok(b) = arg.parse() {
+            bonus = b;
+        } else if let Ok(s) = arg.parse::<Seed>() {
+            seed = Some(s);
+        } else if matches!(arg, "daily" | "dagsins") {
+            daily = Some(chrono::Utc::now().date_naive());
+        } else {
+            msg.channel_id.say(&ctx, "Unknown option, try `boggle`, `dice`, `letters`, `brackets`, `rarity`, `first`, `unique`, `daily`, a size of 4, 5 or 6 or the seed of a board.").await?;
+            return Ok(())
+        }
+    }
+
+    if let Some(date) = daily {
+        let played = ctx.data.write().await.entry::<WordGameStats>().or_insert_with(WordGameStats::load).has_played_daily(date, msg.author.id);
+        if played {
+            msg.channel_id.say(&ctx, "You have already played today's daily challenge, see how everyone did with `]wgstig daily`.").await?;
+            return Ok(())
+        }
+    }
+
+    let word_list = word_list(ctx).await;
+    // Picking a board solves it up to a few times, which is kept off the async workers
+    let (table, seed) = match (daily, seed) {
+        // The daily challenge is always played with the same rules
+        (Some(date), _) => {
+            mode = Mode::Boggle;
+            scoring = Scoring::default();
+            bonus = Bonus::default();
+            let kept = ctx.data.read().await.get::<WordGameStats>().and_then(|stats| stats.daily_seed(date));
+            let seed = match kept {
+                Some(seed) => seed,
+                None => {
+                    let seed = tokio::task::spawn_blocking(move || Seed::daily(date, &word_list)).await?;
+                    let mut data = ctx.data.write().await;
+                    let stats = data.entry::<WordGameStats>().or_insert_with(WordGameStats::load);
+                    let seed = stats.keep_daily_seed(date, seed);
+                    if let Err(e) = stats.save() {
+                        eprintln!("Could not save word game stats: {}", e);
+                    }
+                    seed
+                }
+            };
+            (seed.table(), seed)
+        }
+        (None, Some(seed)) => (seed.table(), seed),
+        (None, None) => tokio::task::spawn_blocking(move || wordgame::gen_table(width, generation, mode, &word_list)).await?,
+    };
+    let picture = board_picture(ctx, &table, &[]).await;
+    let board = if picture.is_some() { String::new() } else { wordgame::format_table(&table) };
+    let content = match mode {
+        Mode::Free => board,
+        Mode::Boggle => format!("{}\nWords must follow neighbouring letters without reusing any.", board),
+    };
+    let content = match daily {
+        Some(date) => format!("**Daily challenge {}**\n{}", date, content),
+        None => format!("{}\nSeed: `{}`", content, seed),
+    };
+    let content = format!("{}\nWords need at least {} letters.\n{}", content, table.min_letters(), scoring_line(scoring, bonus));
+    let minutes = guild_settings(ctx, msg.guild_id).await.word_game_minutes;
+    let content = if minutes == 0 {
+        content
+    } else {
+        format!("{}\nThe game ends in {} minutes.", content, minutes)
+    };
+
+    let mut game_msg = CreateMessage::new().content(content.trim_start());
+    if let Some(picture) = picture {
+        game_msg = game_msg.add_file(picture);
+    }
+    let game_msg = msg.channel_id.send_message(&ctx, game_msg).await?;
+    let wgs = WordGameState::new(table, Some(seed), daily, mode, msg.author.id, game_msg).with_scoring(scoring, bonus);
+
+    let game = ctx.data.write().await.entry::<WordGames>().or_default().start(wgs);
+
+    if let Some(game) = game.filter(|_| minutes != 0) {
+        tokio::spawn(game_timer(ctx.clone(), msg.guild_id, msg.channel_id, game, Duration::from_secs(minutes * 60)));
+    }
+
+    Ok(())
+}
+
+/// Reads a board width like `5` or `5x5`
+fn parse_width(s: &str) -> Option<usize> {
+    let (w, h) = s.split_once(['x', '×']).unwrap_or((s, s));
+    let width = w.parse().ok()?;
+
+    (h.parse() == Ok(width) && wordgame::TABLE_WIDTHS.contains(&width)).then_some(width)
+}
+
+/// Warnings given when this much time is left of a word game
+const WORD_GAME_WARNINGS: [(Duration, &str); 2] = [
+    (Duration::from_secs(60), "1 minute left!"),
+    (Duration::from_secs(10), "10 seconds left!"),
+];
+
+/// Gives warnings when the game is about to end and ends it when the time is up,
+/// unless the game was ended or replaced in the meantime
+async fn game_timer(ctx: Context, guild_id: Option<GuildId>, channel_id: ChannelId, game: SharedGame, duration: Duration) {
+    let mut remaining = duration;
+
+    for (left, warning) in WORD_GAME_WARNINGS {
+        if remaining <= left {
+            continue;
+        }
+        sleep(remaining - left).await;
+        remaining = left;
+
+        if !word_game(&ctx, channel_id).await.is_some_and(|g| Arc::ptr_eq(&g, &game)) {
+            return;
+        }
+        if let Err(e) = channel_id.say(&ctx, warning).await {
+            eprintln!("Could not warn about the end of the word game: {}", e);
+        }
+    }
+    sleep(remaining).await;
+
+    let removed = ctx.data.write().await.get_mut::<WordGames>().is_some_and(|games| games.remove_game(channel_id, &game));
+    if removed {
+        if let Err(e) = end_game(&ctx, guild_id, &mut *game.lock().await).await {
+            eprintln!("Could not end word game: {}", e);
+        }
+    }
+}
+
+#[command]
+#[description = "Vote to end the word game in this channel. It ends when more than half of the players have voted."]
+#[aliases(endwordgame, endaorðaspæl)]
+async fn wgend(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
+    let Some(game) = word_game(ctx, msg.channel_id).await else {
+        msg.channel_id.say(&ctx, "There is no word game here.").await?;
+        return Ok(())
+    };
+    let mut wgs = game.lock().await;
+
+    match wgs.vote_end(msg.author.id) {
+        None => {
+            msg.channel_id.say(&ctx, "Only players who have found a word can vote.").await?;
+        }
+        Some((votes, needed)) if votes >= needed => {
+            let removed = ctx.data.write().await.get_mut::<WordGames>().is_some_and(|games| games.remove_game(msg.channel_id, &game));
+
+            msg.react(ctx, '✅').await?;
+            if removed {
+                end_game(ctx, msg.guild_id, &mut wgs).await?;
+            }
+        }
+        Some((votes, needed)) => {
+            msg.react(ctx, '✅').await?;
+            msg.channel_id.say(&ctx, format!("{}/{} votes to end the game.", votes, needed)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[command]
+#[description = "Guess the five-letter Faroese word in six tries! 🟩 is a letter in the right place and 🟨 one that is somewhere else in the word. Start a puzzle, or the `daily` one that is the same for everyone, and guess with `]wordle <word>`. Everyone has their own puzzle."]
+#[usage = "[daily|giveup|<guess>]"]
+#[aliases(orðla, ordla)]
+#[max_args(1)]
+async fn wordle(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let arg = args.current().map(str::to_lowercase);
+    let game = ctx.data.read().await.get::<Wordles>().and_then(|w| w.get(msg.author.id));
+
+    let Some(game) = game else {
+        let daily = match arg.as_deref() {
+            None => None,
+            Some("daily" | "dagsins") => Some(chrono::Utc::now().date_naive()),
+            Some(_) => {
+                msg.channel_id.say(&ctx, "You don't have a puzzle, start one with `]wordle` or `]wordle daily`.").await?;
+                return Ok(())
+            }
+        };
+        let played = match daily {
+            Some(date) => ctx.data.write().await.entry::<Wordles>().or_insert_with(Wordles::load).has_played_daily(msg.author.id, date),
+            None => false,
+        };
+        if played {
+            msg.channel_id.say(&ctx, "You've already played today's puzzle, come back tomorrow!").await?;
+            return Ok(())
+        }
+
+        let answers = wordle::answers(&word_list(ctx).await);
+        let mut data = ctx.data.write().await;
+        let wordles = data.entry::<Wordles>().or_insert_with(Wordles::load);
+        let answer = match daily {
+            Some(date) => wordles.daily_answer(date, &answers),
+            None => wordle::random_answer(&answers).map(str::to_owned),
+        };
+        let Some(answer) = answer else {
+            drop(data);
+            msg.channel_id.say(&ctx, "I don't know any five-letter words yet, play some word games first!").await?;
+            return Ok(())
+        };
+
+        wordles.start(msg.author.id, Wordle::new(&answer, daily));
+        if daily.is_some() {
+            if let Err(e) = wordles.save() {
+                eprintln!("Could not save daily puzzles: {}", e);
+            }
+        }
+        drop(data);
+        msg.channel_id.say(&ctx, format!(
+            "Your puzzle has started! Guess the five-letter word in {} tries with `]wordle <word>`.",
+            wordle::MAX_GUESSES,
+        )).await?;
+        return Ok(())
+    };
+
+    let mut wordle = game.lock().await;
+    match arg.as_deref() {
+        None => (),
+        Some("giveup" | "gevst") => {
+            ctx.data.write().await.entry::<Wordles>().or_insert_with(Wordles::load).remove(msg.author.id);
+            msg.channel_id.say(&ctx, format!("The word was **{}**.", wordle.answer())).await?;
+            return Ok(())
+        }
+        Some("daily" | "dagsins") => {
+            msg.channel_id.say(&ctx, "You already have a puzzle running, finish it or use `]wordle giveup` first.").await?;
+            return Ok(())
+        }
+        Some(guess) => {
+            let error = match wordle.guess(&http_client(ctx).await, &word_list(ctx).await, guess).await {
+                Ok(_) => None,
+                Err(WordleError::WrongLength) => Some(format!("Guesses must have {} letters.", wordle::WORD_LENGTH)),
+                Err(WordleError::NotFaroese) => Some("Guesses can only have Faroese letters.".to_owned()),
+                Err(WordleError::AlreadyGuessed) => Some("You've already guessed that.".to_owned()),
+                Err(WordleError::NotFound) => Some(format!("_{}_ not found in a dictionary.", guess)),
+                Err(WordleError::LookupFailed(e)) => Some(format!("Couldn't check your guess on Sprotin ({e}), try again.")),
+            };
+            if let Some(error) = error {
+                msg.react(&ctx, '❌').await?;
+                msg.channel_id.say(&ctx, error).await?;
+                return Ok(())
+            }
+        }
+    }
+
+    let mut content = wordle.board();
+    if wordle.is_over() {
+        ctx.data.write().await.entry::<Wordles>().or_insert_with(Wordles::load).remove(msg.author.id);
+
+        if wordle.is_won() {
+            content.push_str("\nYou got it!");
+        } else {
+            content.push_str(&format!("\nThe word was **{}**.", wordle.answer()));
+        }
+        content.push_str(&format!("\n\n{}", wordle.share()));
+    } else {
+        let mut keys: [Vec<char>; 4] = Default::default();
+        for (c, tile) in wordle.keyboard() {
+            let i = match tile {
+                None => 0,
+                Some(Tile::Correct) => 1,
+                Some(Tile::Present) => 2,
+                Some(Tile::Absent) => 3,
+            };
+            keys[i].push(c);
+        }
+        content.push('\n');
+        for (marker, keys) in ['⬜', '🟩', '🟨', '⬛'].into_iter().zip(keys).filter(|(_, k)| !k.is_empty()) {
+            let keys: Vec<String> = keys.into_iter().map(|c| c.to_uppercase().to_string()).collect();
+            content.push_str(&format!("{} {}\n", marker, keys.join(" ")));
+        }
+        content.push_str(&format!("{} guesses left", wordle::MAX_GUESSES - wordle.guesses.len()));
+    }
+
+    msg.channel_id.say(&ctx, content).await?;
+
+    Ok(())
+}
+
+#[command]
+#[description = "Start a game of hangman with a word from Sprotin! Guess by sending a letter on its own, and every few wrong guesses give a hint from the word's entry."]
+#[usage = "[giveup]"]
+#[max_args(1)]
+async fn hangman(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    if matches!(args.current(), Some("giveup" | "gevst")) {
+        let game = ctx.data.write().await.get_mut::<Hangmans>().and_then(|games| games.remove(msg.channel_id));
+        match game {
+            Some(game) => end_hangman(ctx, msg, &game).await?,
+            None => {
+                msg.channel_id.say(&ctx, "There is no game of hangman here.").await?;
+            }
+        }
+        return Ok(())
+    }
+    if ctx.data.read().await.get::<Hangmans>().is_some_and(|games| games.contains(msg.channel_id)) {
+        // If a game is already happening here, don't replace it
+        msg.react(ctx, '🔂').await?;
+        return Ok(())
+    }
+
+    let (word, entry) = match hangman::pick_word(&http_client(ctx).await, &word_list(ctx).await).await {
+        Ok(Some(picked)) => picked,
+        Ok(None) => {
+            msg.channel_id.say(&ctx, "I don't know enough words yet, play some word games first!").await?;
+            return Ok(())
+        }
+        Err(e) => {
+            msg.channel_id.say(&ctx, format!("Couldn't find a word on Sprotin ({e}), try again.")).await?;
+            return Ok(())
+        }
+    };
+
+    let mut game = Hangman::new(&word, entry);
+    game.message = msg.channel_id.say(&ctx, game.status()).await?;
+
+    if !ctx.data.write().await.entry::<Hangmans>().or_default().start(game) {
+        msg.react(ctx, '🔂').await?;
+    }
+
+    Ok(())
+}
+/// Reveals the word with its full entry
+async fn end_hangman(ctx: &Context, msg: &Message, game: &Hangman) -> CommandResult {
+    let mut mmb = MsgBunchBuilder::new();
+    if game.is_won() {
+        mmb.add_lines(format!("You got it, the word was **{}**!\n", game.word()));
+    } else {
+        mmb.add_lines(format!("The word was **{}**.\n", game.word()));
+    }
+    game.entry.to_full_string(&mut mmb);
+
+    send_bunch(ctx, msg, mmb.build()).await?;
+
+    Ok(())
+}
+async fn hangman_guess(ctx: &Context, msg: &Message, c: char) -> CommandResult {
+    // Only take the write lock where a game is going on, since every message of one character comes here
+    if !ctx.data.read().await.get::<Hangmans>().is_some_and(|games| games.contains(msg.channel_id)) {
+        return Ok(())
+    }
+    let (guess, status, mut message, finished) = {
+        let mut data = ctx.data.write().await;
+        let Some(games) = data.get_mut::<Hangmans>() else { return Ok(()) };
+        let Some(game) = games.get_mut(msg.channel_id) else { return Ok(()) };
+
+        let guess = game.guess(c);
+        let status = game.status();
+        let message = game.message.clone();
+        let finished = if game.is_over() { games.remove(msg.channel_id) } else { None };
+
+        (guess, status, message, finished)
+    };
+
+    match guess {
+        Guess::Hit => msg.react(&ctx, '✅').await?,
+        Guess::Miss => msg.react(&ctx, '❌').await?,
+        Guess::AlreadyGuessed => msg.react(&ctx, ReactionType::Unicode("♻️".to_owned())).await?,
+        Guess::NotALetter => return Ok(()),
+    };
+    message.edit(&ctx, EditMessage::new().content(status)).await?;
+
+    if let Some(game) = finished {
+        end_hangman(ctx, msg, &game).await?;
+    }
+
+    Ok(())
+}
+
+#[command]
+#[description = "Start an anagram game! Each round deals some letters and whoever is first to post the longest word made from them gets its points. Guess by typing `.` or `:` followed by your guess(es)."]
+#[usage = "[6-12]"]
+#[max_args(1)]
+async fn anagram(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let rack_size = if args.is_empty() {
+        anagram::DEFAULT_RACK_SIZE
+    } else {
+        match args.single::<usize>() {
+            Ok(n) if anagram::RACK_SIZES.contains(&n) => n,
+            _ => {
+                msg.channel_id.say(&ctx, format!(
+                    "The number of letters must be from {} to {}.",
+                    anagram::RACK_SIZES.start(), anagram::RACK_SIZES.end(),
+                )).await?;
+                return Ok(())
+            }
+        }
+    };
+    if word_game(ctx, msg.channel_id).await.is_some() || anagram_game(ctx, msg.channel_id).await.is_some() {
+        // If a game is already happening here, don't replace it
         msg.react(ctx, '🔂').await?;
         return Ok(())
     }
 
-    let table = wordgame::gen_table();
-    let msg = msg.channel_id.say(&ctx, wordgame::format_table(&table)).await?;
-    let wgs = wordgame::WordGameState::new(table, msg);
+    let mut game = AnagramGame::new(rack_size, msg.author.id);
+    game.round.message = msg.channel_id.say(&ctx, anagram_round_message(&game)).await?;
 
-    ctx.data.write().await.insert::<wordgame::WordGameState>(wgs);
+    let game = ctx.data.write().await.entry::<AnagramGames>().or_default().start(game);
+    if let Some(game) = game {
+        tokio::spawn(anagram_rounds(ctx.clone(), msg.channel_id, game));
+    }
+
+    Ok(())
+}
+fn anagram_round_message(game: &AnagramGame) -> String {
+    format!(
+        "**Round {} of {}**\n{}\nPost the longest word you can make from these letters! Words need at least {} letters.\nType `.` or `:` followed by your guess(es)",
+        game.round_number, anagram::ROUNDS, wordgame::format_table(&game.round.table), game.round.table.min_letters(),
+    )
+}
+/// Ends each round when its time is up and deals the next, until the last round is over
+/// or the game was replaced in the meantime
+async fn anagram_rounds(ctx: Context, channel_id: ChannelId, game: SharedAnagram) {
+    loop {
+        sleep(anagram::ROUND_TIME).await;
+
+        if !anagram_game(&ctx, channel_id).await.is_some_and(|g| Arc::ptr_eq(&g, &game)) {
+            return;
+        }
+        match end_anagram_round(&ctx, channel_id, &game).await {
+            Ok(true) => (),
+            Ok(false) => return,
+            Err(e) => {
+                eprintln!("Could not end anagram round: {}", e);
+                ctx.data.write().await.entry::<AnagramGames>().or_default().remove_game(channel_id, &game);
+                return;
+            }
+        }
+    }
+}
+/// Posts who won the round and deals the next one, giving whether there is a next one
+async fn end_anagram_round(ctx: &Context, channel_id: ChannelId, game: &SharedAnagram) -> serenity::Result<bool> {
+    let mut game_lock = game.lock().await;
+    let solution = game_lock.round.solve(&word_list(ctx).await);
+
+    let mut content = match game_lock.end_round() {
+        Some(winner) => format!("<@{}> wins round {} with **{}** for {} points!", winner.user, game_lock.round_number, winner.word, winner.points),
+        None => format!("Nobody found a word in round {}.", game_lock.round_number),
+    };
+    if let Some(longest) = solution.longest_word() {
+        content.push_str(&format!("\nThe longest known word was **{}**.", longest));
+    }
+
+    let last = game_lock.is_last_round();
+    if last {
+        ctx.data.write().await.entry::<AnagramGames>().or_default().remove_game(channel_id, game);
+
+        content.push_str("\n\nThe anagram game is over!\n");
+        let ranking = game_lock.ranking();
+        for (i, (user, points)) in ranking.iter().enumerate() {
+            let line = format!("{}. <@{}>: {} ({} bókstavir, {} orð)", i + 1, user, points.points, points.letters, points.words);
+            if points.points == ranking[0].1.points {
+                content.push_str(&format!("🏆 **{}**\n", line));
+            } else {
+                content.push_str(&format!("{}\n", line));
+            }
+        }
+    }
+    channel_id.send_message(ctx, CreateMessage::new()
+        .content(content)
+        .allowed_mentions(CreateAllowedMentions::new().empty_users())
+    ).await?;
+
+    if !last {
+        game_lock.next_round();
+        game_lock.round.message = channel_id.say(ctx, anagram_round_message(&game_lock)).await?;
+    }
+
+    Ok(!last)
+}
+async fn anagram_guess(ctx: &Context, client: &HttpClient, word_list: &WordList, word: &str, msg: &Message, game: &mut AnagramGame) -> CommandResult {
+    let word = serenity::utils::content_safe(ctx, word, &ContentSafeOptions::default(), &[]);
+
+    match game.guess_word(client, word_list, msg.author.id, word).await {
+        Ok(()) => {
+            msg.react(&ctx, '✅').await?;
+        }
+        Err(e) => report_guess_error(ctx, msg, e).await?,
+    }
+
+    Ok(())
+}
+
+#[command]
+#[description = "Show the word game leaderboard of the server and your own stats, or the results of today's daily challenge"]
+#[usage = "[week|month|all|daily]"]
+#[aliases(wordgamestats, orðaspælstig)]
+#[max_args(1)]
+async fn wgstig(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let guild_id = msg.guild_id.ok_or("only in guilds")?;
+    if matches!(args.current(), Some("daily" | "dagsins")) {
+        return daily_board(ctx, msg).await;
+    }
+    let period = if args.is_empty() {
+        Period::AllTime
+    } else if let Ok(period) = args.single() {
+        period
+    } else {
+        msg.channel_id.say(&ctx, "Unknown period, try `week`, `month` or `all`.").await?;
+        return Ok(())
+    };
+
+    let (leaderboard, player) = {
+        let data = ctx.data.read().await;
+        match data.get::<WordGameStats>() {
+            Some(stats) => (stats.leaderboard(guild_id, period, chrono::Utc::now()), stats.player(guild_id, msg.author.id)),
+            None => (Vec::new(), None),
+        }
+    };
+
+    let title = match period {
+        Period::AllTime => "All-time leaderboard",
+        Period::Month => "Leaderboard this month",
+        Period::Week => "Leaderboard this week",
+    };
+    let mut content = format!("**{}**\n", title);
+
+    if leaderboard.is_empty() {
+        content.push_str("No games have been played.\n");
+    }
+    for (i, (user, points)) in leaderboard.iter().take(10).enumerate() {
+        content.push_str(&format!("{}. <@{}>: {}\n", i + 1, user, points));
+    }
+
+    if let Some(player) = player {
+        content.push_str(&format!(
+            "\n**Your stats**\nGames played: {}\nBest game: {}\nAverage points: {}\nLongest word: {}",
+            player.games, player.best_game, player.average_points, player.longest_word,
+        ));
+    }
+
+    msg.channel_id.send_message(&ctx, CreateMessage::new()
+        .content(content)
+        .allowed_mentions(CreateAllowedMentions::new().empty_users())
+    ).await?;
+
+    Ok(())
+}
+
+/// Shows how everyone did in today's daily challenge, across all guilds
+async fn daily_board(ctx: &Context, msg: &Message) -> CommandResult {
+    let today = chrono::Utc::now().date_naive();
+    let board: Vec<_> = {
+        let data = ctx.data.read().await;
+        match data.get::<WordGameStats>() {
+            Some(stats) => stats.daily_leaderboard(today).into_iter().map(|(_, r)| (r.name.clone(), r.points)).collect(),
+            None => Vec::new(),
+        }
+    };
+
+    let mut content = format!("**Daily challenge {}**\n", today);
+    if board.is_empty() {
+        content.push_str("No one has played today's challenge yet, start it with `]wg daily`.\n");
+    }
+    for (i, (name, points)) in board.iter().take(10).enumerate() {
+        content.push_str(&format!("{}. {}: {}\n", i + 1, name, points));
+    }
+
+    msg.channel_id.say(&ctx, content).await?;
 
     Ok(())
 }
 
 #[command]
-#[description = "Stop current word game!"]
+#[description = "Look up the given words on Sprotin and add them to the word list of the word game. Without any words, all the headwords in it are looked up again"]
+#[usage = "[words...]"]
+#[aliases(orðalisti)]
+async fn wordlist(ctx: &Context, msg: &Message, args: Args) -> CommandResult {
+    let client = http_client(ctx).await;
+    let word_list = word_list(ctx).await;
+    let words: Vec<String> = if args.is_empty() {
+        word_list.headwords()
+    } else {
+        args.raw().map(str::to_owned).collect()
+    };
+
+    msg.channel_id.say(&ctx, format!("Looking up {} words…", words.len())).await?;
+
+    let before = word_list.len();
+    let failed = wordgame::refresh_word_list(&client, &word_list, &words).await;
+    if let Err(e) = word_list.save() {
+        eprintln!("Could not save word list: {}", e);
+    }
+
+    msg.channel_id.say(&ctx, format!(
+        "The word list has {} forms, {} of them new. {} lookups failed.",
+        word_list.len(), word_list.len() - before, failed,
+    )).await?;
+
+    Ok(())
+}
+
+#[command]
+#[description = "Stop the word game in this channel!"]
 #[aliases(deletewordgame, nýttorðaspæl)]
 async fn wgdel(ctx: &Context, msg: &Message, _args: Args) -> CommandResult {
-    ctx.data.write().await.remove::<wordgame::WordGameState>();
+    let game = ctx.data.write().await.get_mut::<WordGames>().and_then(|games| games.remove(msg.channel_id));
 
     msg.react(ctx, '✅').await?;
 
+    if let Some(game) = game {
+        end_game(ctx, msg.guild_id, &mut *game.lock().await).await?;
+    }
+
+    Ok(())
+}
+
+/// Posts the final scoreboard, the words nobody found and how much of the possible score was reached
+async fn end_game(ctx: &Context, guild_id: Option<GuildId>, wgs: &mut WordGameState) -> CommandResult {
+    wgs.give_bonuses();
+    let word_list = word_list(ctx).await;
+    let solution = wgs.solve(&word_list);
+
+    if let Err(e) = word_list.save() {
+        eprintln!("Could not save word list: {}", e);
+    }
+
+    let mut names = HashMap::new();
+    if wgs.daily.is_some() {
+        for &user in wgs.guessers.keys() {
+            let name = user.to_user(ctx).await.map(|u| u.name).unwrap_or_else(|_| user.to_string());
+            names.insert(user, name);
+        }
+    }
+
+    if !wgs.guessers.is_empty() {
+        let mut data = ctx.data.write().await;
+        let stats = data.entry::<WordGameStats>().or_insert_with(WordGameStats::load);
+        let time = chrono::Utc::now();
+
+        for (&user, points) in &wgs.guessers {
+            if let Some(guild_id) = guild_id {
+                stats.record(guild_id, user, GameRecord {
+                    time,
+                    points: points.points,
+                    letters: points.letters,
+                    words: points.words,
+                    longest_word: points.longest_word.clone(),
+                });
+            }
+            if let Some(date) = wgs.daily {
+                stats.record_daily(date, user, DailyRecord {
+                    name: names.remove(&user).unwrap_or_default(),
+                    points: points.points,
+                });
+            }
+        }
+        if let Err(e) = stats.save() {
+            eprintln!("Could not save word game stats: {}", e);
+        }
+    }
+
+    let mut mmb = MsgBunchBuilder::new();
+    mmb.add_string("The game is over!\n").add_string(wordgame::format_table(&wgs.table)).add_string("\n");
+    match (wgs.daily, wgs.seed) {
+        (Some(date), _) => {
+            mmb.add_lines(format!("Daily challenge {}, see how everyone did with `]wgstig daily`\n", date));
+        }
+        (None, Some(seed)) => {
+            mmb.add_lines(format!("Play this board again with `]wg {}`\n", seed));
+        }
+        (None, None) => (),
+    }
+    mmb.add_lines(scoring_line(wgs.scoring, wgs.bonus));
+
+    let ranking = wgs.ranking();
+    if ranking.is_empty() {
+        mmb.add_lines("Nobody found any words.\n");
+    }
+    for (i, (user, points)) in ranking.iter().enumerate() {
+        let mut line = format!("{}. <@{}>: {} ({} bókstavir, {} orð)", i + 1, user, points.points, points.letters, points.words);
+        if points.bonus != 0 {
+            line.push_str(&format!(", {} in bonus", points.bonus));
+        }
+        if points.points == ranking[0].1.points {
+            mmb.add_lines(format!("🏆 **{}**", line));
+        } else {
+            mmb.add_lines(line);
+        }
+    }
+    mmb.add_string("\n");
+
+    if let Some(longest) = solution.longest_word() {
+        let percentage = wgs.points() as f64 / solution.total_points as f64 * 100.;
+        mmb.add_lines(format!(
+            "Longest possible word: **{}**\nYou got {} of {} possible points ({:.0}%)\n",
+            longest, wgs.points(), solution.total_points, percentage,
+        ));
+    }
+
+    let mut rules: Vec<_> = wgs.rules.iter().filter(|(_, rule)| !matches!(rule, Rule::Headword | Rule::InflectedForm(_))).collect();
+    if !rules.is_empty() {
+        rules.sort_by_key(|(word, _)| *word);
+        mmb.add_lines("Words accepted by other rules:");
+        for (word, rule) in rules {
+            mmb.add_lines(format!("{}: {}", word, rule));
+        }
+        mmb.add_string("\n");
+    }
+
+    let missed: Vec<_> = solution.missed_words(&wgs.taken_words).collect();
+    if missed.is_empty() {
+        mmb.add_lines("No known words were missed!");
+    } else {
+        mmb.add_lines(format!("Missed words: {}", missed.join(", ")));
+    }
+
+    send_bunch_to(ctx, guild_id, wgs.message.channel_id, wgs.starter, mmb.build()).await?;
+
     Ok(())
 }
 
 #[group]
-#[commands(gm, sa, sai, sprotin, fof, foe, enf, fod, daf, daf2, fot, tyf, fos, spf, grf, frf, foi, ruf, fok, kif, sam, navn, alfr, tilt, yrk, busk, num, wg)]
+#[commands(gm, gmi, sa, sai, sprotin, fof, foe, enf, fod, daf, daf2, fot, tyf, fos, spf, grf, frf, foi, ruf, fok, kif, sam, navn, alfr, tilt, yrk, busk, num, wg, wgend, wgstig, wordle, hangman, anagram)]
 #[only_in("guilds")]
 #[help_available]
 struct General;
 
+#[command]
+#[description = "Set how many messages long results can be before they are sent as a file instead. 0 never sends them as a file"]
+#[usage = "<messages>"]
+#[aliases(fílumørk)]
+#[num_args(1)]
+async fn filethreshold(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let threshold = args.single()?;
+    let guild_id = msg.guild_id.ok_or("only in guilds")?;
+
+    {
+        let mut data = ctx.data.write().await;
+        let settings = data.entry::<Settings>().or_insert_with(Settings::load);
+        settings.guild_mut(guild_id).attachment_threshold = threshold;
+        settings.save()?;
+    }
+
+    msg.react(ctx, '✅').await?;
+
+    Ok(())
+}
+
+#[command]
+#[description = "Set how many minutes word games last. 0 lets them go on until they are ended"]
+#[usage = "<minutes>"]
+#[aliases(orðaspæltíð)]
+#[num_args(1)]
+async fn wgtime(ctx: &Context, msg: &Message, mut args: Args) -> CommandResult {
+    let minutes = args.single()?;
+    let guild_id = msg.guild_id.ok_or("only in guilds")?;
+
+    {
+        let mut data = ctx.data.write().await;
+        let settings = data.entry::<Settings>().or_insert_with(Settings::load);
+        settings.guild_mut(guild_id).word_game_minutes = minutes;
+        settings.save()?;
+    }
+
+    msg.react(ctx, '✅').await?;
+
+    Ok(())
+}
+
 #[group]
+#[commands(filethreshold, wgtime)]
 #[only_in("guilds")]
 #[required_permissions(ADMINISTRATOR)]
 struct ModOnly;
 
 #[group]
-#[commands(setgame, say, wgdel)]
+#[commands(setgame, say, wgdel, wordlist)]
 #[owners_only]
 #[only_in("guilds")]
 struct Owner;
@@ -403,7 +1229,13 @@ async fn main() {
         ).await.expect("Could not make client");
 
     {
-        // let mut data = client.data.write();
+        let mut data = client.data.write().await;
+        data.insert::<HttpClient>(HttpClient::new());
+        data.insert::<Settings>(Settings::load());
+        data.insert::<WordList>(WordList::load());
+        data.insert::<WordGameStats>(WordGameStats::load());
+        data.insert::<Wordles>(Wordles::load());
+        data.insert::<BoardPainter>(BoardPainter::load());
     }
 
     if let Err(why) = client.start().await {
@@ -430,13 +1262,32 @@ impl EventHandler for Handler {
             return
         }
 
-        if let Some(wgs) = ctx.data.write().await.get_mut::<wordgame::WordGameState>() {
-            if msg.channel_id == wgs.message.channel_id {
-                if let Some(s) = msg.content.strip_prefix([':', '.', ';']) {
-                    for guess in s.split_whitespace() {
-                        word_guess(&ctx, guess, &msg, wgs).await.unwrap();
+        let client = http_client(&ctx).await;
+        let word_list = word_list(&ctx).await;
+
+        if let Some(c) = hangman::single_letter(&msg.content) {
+            if let Err(e) = hangman_guess(&ctx, &msg, c).await {
+                eprintln!("Could not handle hangman guess: {}", e);
+            }
+        }
+
+        if let Some(game) = word_game(&ctx, msg.channel_id).await {
+            if let Some(s) = msg.content.strip_prefix([':', '.', ';']) {
+                let mut wgs = game.lock().await;
+                for guess in s.split_whitespace() {
+                    if let Err(e) = word_guess(&ctx, &client, &word_list, guess, &msg, &mut wgs).await {
+                        eprintln!("Could not handle guess: {}", e);
+                    }
+                }
+                // Ignore
+            }
+        } else if let Some(game) = anagram_game(&ctx, msg.channel_id).await {
+            if let Some(s) = msg.content.strip_prefix([':', '.', ';']) {
+                let mut game = game.lock().await;
+                for guess in s.split_whitespace() {
+                    if let Err(e) = anagram_guess(&ctx, &client, &word_list, guess, &msg, &mut game).await {
+                        eprintln!("Could not handle anagram guess: {}", e);
                     }
-                    // Ignore
                 }
             }
         }
@@ -459,36 +1310,61 @@ impl EventHandler for Handler {
     }
 }
 
-async fn word_guess(ctx: &Context, word: &str, msg: &Message, wgs: &mut WordGameState) -> CommandResult {
+async fn word_guess(ctx: &Context, client: &HttpClient, word_list: &WordList, word: &str, msg: &Message, wgs: &mut WordGameState) -> CommandResult {
     let word = serenity::utils::content_safe(ctx, word, &ContentSafeOptions::default(), &[]);
 
-    match wgs.guess_word(msg.author.id, word).await {
+    match wgs.guess_word(client, word_list, msg.author.id, word).await {
         Ok(()) => {
             msg.react(&ctx, '✅').await?;
             let mut winners = String::new();
             for (user, points) in &wgs.guessers {
                 winners.push_str(&format!("<@{}>: {} ({} bókstavir, {} orð)\n", user, points.points, points.letters, points.words));
             }
-            let table = wordgame::format_table(&wgs.table);
-            let cntnt = format!("Taken words: {}\n\n{winners}\n{table}\nType `.` or `:` followed by your guess(es)", wgs.taken_words.join(", "));
+            // The text board is only needed if there's no picture
+            let picture = board_picture(ctx, &wgs.table, &wgs.last_path).await;
+            let table = if picture.is_some() { String::new() } else { wordgame::format_table(&wgs.table) };
+            let scoring = scoring_line(wgs.scoring, wgs.bonus);
+            let cntnt = format!("Taken words: {}\n\n{winners}\n{scoring}\n{table}\nType `.` or `:` followed by your guess(es)", wgs.taken_words.join(", "));
 
-            if wgs.taken_words.len() % 6 == 0 {
-                wgs.message = msg.channel_id.say(&ctx, table).await?;
+            // Count every find, as with a bonus more players can find the same word
+            let finds: u32 = wgs.guessers.values().map(|p| p.words).sum();
+            if finds % 6 == 0 {
+                wgs.message = msg.channel_id.say(&ctx, wordgame::format_table(&wgs.table)).await?;
+            }
+            let mut edit = EditMessage::new().content(cntnt);
+            if let Some(picture) = picture {
+                edit = edit.attachments(EditAttachments::new().add(picture));
             }
-            wgs.message.edit(&ctx, EditMessage::new().content(cntnt)).await?;
+            wgs.message.edit(&ctx, edit).await?;
         }
-        Err(GuessError::AlreadyGuessed) => {
+        Err(e) => report_guess_error(ctx, msg, e).await?,
+    }
+
+    Ok(())
+}
+/// Tells the player why their guess wasn't accepted
+async fn report_guess_error(ctx: &Context, msg: &Message, error: GuessError) -> CommandResult {
+    match error {
+        GuessError::AlreadyGuessed => {
             msg.react(&ctx, ReactionType::Unicode("♻️".to_owned())).await?;
         }
-        Err(GuessError::NotFound(word)) => {
+        GuessError::NotFound(word) => {
             msg.react(&ctx, '❌').await?;
             msg.channel_id.say(&ctx, format!("_{word}_ not found in a dictionary.")).await?;
         }
-        Err(GuessError::WrongLetters) => {
+        GuessError::WrongLetters => {
             msg.react(&ctx, '❌').await?;
             msg.channel_id.say(&ctx, "You used letters not in the game.").await?;
         }
-        Err(GuessError::TooShort) => {
+        GuessError::NotAdjacent => {
+            msg.react(&ctx, '❌').await?;
+            msg.channel_id.say(&ctx, "Your guess doesn't follow neighbouring letters.").await?;
+        }
+        GuessError::LookupFailed(e) => {
+            msg.react(&ctx, '⚠').await?;
+            msg.channel_id.say(&ctx, format!("Couldn't check your guess on Sprotin ({e}), try again.")).await?;
+        }
+        GuessError::TooShort => {
             msg.react(&ctx, '❌').await?;
             msg.channel_id.say(&ctx, "Your guess was too short.").await?;
         }
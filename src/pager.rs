@@ -0,0 +1,173 @@
+//! Showing an [`MsgBunch`] as a single message that can be paged through with buttons,
+//! or as a file if it is too long for that, and paging through an [`EmbedBunch`] the same way.
+
+use std::time::Duration;
+
+use serenity::all::{
+    ButtonStyle,
+    ChannelId,
+    Context,
+    CreateActionRow,
+    CreateAttachment,
+    CreateButton,
+    CreateEmbed,
+    CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+    CreateMessage,
+    EditMessage,
+    Message,
+    UserId,
+};
+
+use crate::util::{Embed, EmbedBunch, MsgBunch};
+
+/// How long the buttons keep working after they were last used
+const PAGER_TIMEOUT: Duration = Duration::from_secs(120);
+/// Shown under the messages if something had to be split in the middle of a word
+const HARD_SPLIT_NOTE: &str = "_Ein tekstur var ov langur og er býttur sundur mitt í einum orði._";
+
+const PREVIOUS: &str = "pager_previous";
+const NEXT: &str = "pager_next";
+
+fn buttons(page: usize, pages: usize) -> Vec<CreateActionRow> {
+    vec![CreateActionRow::Buttons(vec![
+        CreateButton::new(PREVIOUS).label("◀").style(ButtonStyle::Secondary).disabled(page == 0),
+        CreateButton::new("pager_page").label(format!("{}/{}", page + 1, pages)).style(ButtonStyle::Secondary).disabled(true),
+        CreateButton::new(NEXT).label("▶").style(ButtonStyle::Secondary).disabled(page + 1 == pages),
+    ])]
+}
+
+/// Sends the first message of the bunch with buttons to go to the other messages,
+/// which only `user` can use.
+///
+/// If the bunch is only one message, it is just sent as is.
+/// The message is edited in place when paging and the buttons are removed
+/// after they haven't been used for a while.
+pub async fn send_paged(ctx: &Context, channel_id: ChannelId, user: UserId, mut bunch: MsgBunch) -> serenity::Result<Message> {
+    if bunch.has_hard_split() {
+        bunch.push_note(HARD_SPLIT_NOTE);
+    }
+    let MsgBunch { messages: mut pages, .. } = bunch;
+    pages.retain(|page| !page.trim().is_empty());
+
+    if pages.len() <= 1 {
+        return channel_id.say(ctx, pages.pop().unwrap_or_default()).await;
+    }
+
+    let msg = channel_id.send_message(ctx, CreateMessage::new()
+        .content(&pages[0])
+        .components(buttons(0, pages.len()))
+    ).await?;
+
+    let count = pages.len();
+    turn_pages(ctx, msg.clone(), user, count, move |page| CreateInteractionResponseMessage::new().content(&pages[page]));
+
+    Ok(msg)
+}
+
+/// Lets `user` turn the `count` pages of `msg` with its buttons,
+/// where `page` makes the message for the page turned to
+fn turn_pages<F>(ctx: &Context, mut pager_msg: Message, user: UserId, count: usize, page: F)
+where F: Fn(usize) -> CreateInteractionResponseMessage + Send + 'static {
+    let ctx = ctx.clone();
+
+    tokio::spawn(async move {
+        let mut current: usize = 0;
+
+        while let Some(interaction) = pager_msg.await_component_interaction(&ctx.shard)
+            .author_id(user)
+            .timeout(PAGER_TIMEOUT)
+            .await
+        {
+            match &*interaction.data.custom_id {
+                PREVIOUS => current = current.saturating_sub(1),
+                NEXT => current = (current + 1).min(count - 1),
+                _ => continue,
+            }
+
+            let response = page(current).components(buttons(current, count));
+
+            if let Err(e) = interaction.create_response(&ctx, CreateInteractionResponse::UpdateMessage(response)).await {
+                eprintln!("Could not turn page: {}", e);
+                break;
+            }
+        }
+
+        if let Err(e) = pager_msg.edit(&ctx, EditMessage::new().components(Vec::new())).await {
+            eprintln!("Could not remove pager buttons: {}", e);
+        }
+    });
+}
+
+/// Sends the bunch as a file with a short summary if it is more than `threshold` messages,
+/// and otherwise pages through it with [`send_paged`].
+///
+/// A `threshold` of 0 means it is never sent as a file.
+pub async fn send_bunch(ctx: &Context, channel_id: ChannelId, user: UserId, bunch: MsgBunch, threshold: usize) -> serenity::Result<Message> {
+    if threshold == 0 || bunch.messages.len() <= threshold {
+        return send_paged(ctx, channel_id, user, bunch).await;
+    }
+
+    let summary = bunch.messages.iter()
+        .flat_map(|msg| msg.lines())
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or("");
+    let content = format!("{}\n_Alt úrslitið ({} boð) er í fíluni._", summary, bunch.messages.len());
+
+    channel_id.send_message(ctx, CreateMessage::new()
+        .content(content)
+        .add_file(as_file(bunch))
+    ).await
+}
+
+/// Sends the embeds in as few messages as Discord allows, paged like [`send_paged`] if they need more than one.
+///
+/// If they would take more than `threshold` messages, `text` is sent with [`send_bunch`] instead,
+/// so that it goes in a file.
+/// Discord doesn't allow empty embeds, so an empty bunch is sent as a message saying so.
+pub async fn send_embeds(ctx: &Context, channel_id: ChannelId, user: UserId, bunch: EmbedBunch, text: MsgBunch, threshold: usize) -> serenity::Result<Message> {
+    let pages: Vec<Vec<CreateEmbed>> = bunch.messages()
+        .into_iter()
+        .map(|embeds| embeds.iter().map(Embed::to_create_embed).collect())
+        .collect();
+
+    match pages.len() {
+        0 => return channel_id.say(ctx, "Einki at vísa.").await,
+        1 => return channel_id.send_message(ctx, CreateMessage::new().embeds(pages[0].clone())).await,
+        n if threshold != 0 && n > threshold => return send_bunch(ctx, channel_id, user, text, threshold).await,
+        _ => (),
+    }
+
+    let msg = channel_id.send_message(ctx, CreateMessage::new()
+        .embeds(pages[0].clone())
+        .components(buttons(0, pages.len()))
+    ).await?;
+
+    let count = pages.len();
+    turn_pages(ctx, msg.clone(), user, count, move |page| CreateInteractionResponseMessage::new().embeds(pages[page].clone()));
+
+    Ok(msg)
+}
+
+/// The whole text of the bunch as a file, as it was before it was split into messages
+fn as_file(bunch: MsgBunch) -> CreateAttachment {
+    CreateAttachment::bytes(bunch.source.into_bytes(), "urslit.md")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::as_file;
+    use crate::util::MsgBunchBuilder;
+
+    #[test]
+    fn file_is_unsplit() {
+        let source = format!("**{}**\n{}\n", "hestur ".repeat(400), "a".repeat(3000));
+        let mut mmb = MsgBunchBuilder::new();
+        mmb.add_string(&source);
+        let bunch = mmb.build();
+
+        assert!(bunch.messages.len() > 2);
+        assert_ne!(bunch.messages.concat(), source);
+        assert_eq!(as_file(bunch).data, source.into_bytes());
+    }
+}
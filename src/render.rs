@@ -0,0 +1,218 @@
+//! Renderers that turn styled text into the markup of where it's going to be shown.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::util::{split_trim, to_subscript, to_superscript};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+/// The style of a piece of text
+pub struct CalculatedStyle {
+    pub bold: bool,
+    pub italics: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub superscript: bool,
+    pub subscript: bool,
+}
+
+impl CalculatedStyle {
+    /// Whether the text is bold, italicised, underlined or struck through
+    fn is_decorated(&self) -> bool {
+        self.bold || self.italics || self.underline || self.strikethrough
+    }
+    /// Turns the text into super- or subscript characters if the style calls for it
+    fn script<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if self.superscript {
+            to_superscript(s).into()
+        } else if self.subscript {
+            to_subscript(s).into()
+        } else {
+            s.into()
+        }
+    }
+}
+
+/// Something that builds a string out of pieces of styled text
+pub trait Renderer: Default {
+    /// Adds text with the given style
+    fn push_str(&mut self, s: &str, style: CalculatedStyle);
+    /// Closes whatever styling is still open and gives the rendered text
+    fn build(self) -> String;
+}
+
+#[derive(Debug, Clone, Default)]
+/// Renders into Discord markdown, moving whitespace outside of the markers so Discord picks them up
+///
+/// Whitespace isn't styled, and styles are closed before line breaks and opened again after them,
+/// since Discord doesn't pick up markers around whitespace or across lines reliably.
+pub struct DiscordRenderer {
+    buf: String,
+    last_style: CalculatedStyle,
+    /// How much whitespace is at the end of `buf`, which still can be moved outside of markers
+    last_whitespace_length: usize,
+}
+
+impl DiscordRenderer {
+    /// The markers of the decorations in the order they are opened in, so they are closed in reverse
+    const MARKERS: [&'static str; 4] = ["~~", "_", "**", "__"];
+
+    /// Whether each of the decorations of [`Self::MARKERS`] is on
+    fn decorations(style: CalculatedStyle) -> [bool; 4] {
+        [style.strikethrough, style.italics, style.bold, style.underline]
+    }
+
+    /// Puts `whitespace` between the closing markers of the last style and the opening ones of `style`
+    fn change_style(&mut self, whitespace: &str, style: CalculatedStyle) {
+        let break_line = whitespace.contains('\n');
+        let (last, next) = (Self::decorations(self.last_style), Self::decorations(style));
+
+        let closers: String = (0..Self::MARKERS.len()).rev()
+            .filter(|&i| last[i] && (break_line || !next[i]))
+            .map(|i| Self::MARKERS[i])
+            .collect();
+        let openers: String = (0..Self::MARKERS.len())
+            .filter(|&i| next[i] && (break_line || !last[i]))
+            .map(|i| Self::MARKERS[i])
+            .collect();
+
+        self.buf.push_str(&closers);
+        self.buf.push_str(whitespace);
+        self.buf.push_str(&openers);
+        self.last_style = style;
+    }
+}
+
+impl Renderer for DiscordRenderer {
+    fn push_str(&mut self, s: &str, style: CalculatedStyle) {
+        let c = style.script(s);
+        let s = c.as_ref();
+
+        // The style of whitespace doesn't matter, so it waits for the next text to place the markers
+        if s.trim().is_empty() {
+            self.buf.push_str(s);
+            self.last_whitespace_length += s.len();
+            return;
+        }
+
+        let (start_whitespace, text, end_whitespace) = split_trim(s);
+        let whitespace = self.buf.split_off(self.buf.len() - self.last_whitespace_length) + start_whitespace;
+
+        self.change_style(&whitespace, style);
+        self.buf.push_str(text);
+        self.buf.push_str(end_whitespace);
+        self.last_whitespace_length = end_whitespace.len();
+    }
+    fn build(mut self) -> String {
+        let whitespace = self.buf.split_off(self.buf.len() - self.last_whitespace_length);
+        // Only closing, since whitespace never opens anything
+        self.change_style("", CalculatedStyle::default());
+        self.buf.push_str(&whitespace);
+
+        self.buf
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Renders just the text, with super- and subscript as Unicode characters
+pub struct PlainRenderer {
+    buf: String,
+}
+
+impl Renderer for PlainRenderer {
+    fn push_str(&mut self, s: &str, style: CalculatedStyle) {
+        self.buf.push_str(&style.script(s));
+    }
+    #[inline]
+    fn build(self) -> String {
+        self.buf
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Renders with ANSI escape codes for terminals
+///
+/// Super- and subscript are Unicode characters, since terminals have no codes for them.
+pub struct AnsiRenderer {
+    buf: String,
+    last_style: CalculatedStyle,
+}
+
+impl Renderer for AnsiRenderer {
+    fn push_str(&mut self, s: &str, style: CalculatedStyle) {
+        let decoration = |s: CalculatedStyle| (s.bold, s.italics, s.underline, s.strikethrough);
+
+        if decoration(style) != decoration(self.last_style) {
+            let codes: Vec<_> = [(style.bold, "1"), (style.italics, "3"), (style.underline, "4"), (style.strikethrough, "9")]
+                .into_iter()
+                .filter_map(|(on, code)| on.then_some(code))
+                .collect();
+
+            if self.last_style.is_decorated() {
+                self.buf.push_str("\x1b[0m");
+            }
+            if !codes.is_empty() {
+                write!(self.buf, "\x1b[{}m", codes.join(";")).unwrap();
+            }
+        }
+        self.buf.push_str(&style.script(s));
+
+        self.last_style = style;
+    }
+    fn build(mut self) -> String {
+        if self.last_style.is_decorated() {
+            self.buf.push_str("\x1b[0m");
+        }
+        self.buf
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// Renders into HTML, escaping the text
+pub struct HtmlRenderer {
+    buf: String,
+    last_style: CalculatedStyle,
+}
+
+impl HtmlRenderer {
+    fn tags(style: CalculatedStyle) -> impl DoubleEndedIterator<Item=&'static str> {
+        [
+            (style.bold, "b"),
+            (style.italics, "i"),
+            (style.underline, "u"),
+            (style.strikethrough, "del"),
+            (style.superscript, "sup"),
+            (style.subscript, "sub"),
+        ].into_iter().filter_map(|(on, tag)| on.then_some(tag))
+    }
+    fn close(&mut self) {
+        for tag in Self::tags(self.last_style).rev() {
+            write!(self.buf, "</{}>", tag).unwrap();
+        }
+    }
+}
+
+impl Renderer for HtmlRenderer {
+    fn push_str(&mut self, s: &str, style: CalculatedStyle) {
+        if style != self.last_style {
+            self.close();
+            for tag in Self::tags(style) {
+                write!(self.buf, "<{}>", tag).unwrap();
+            }
+        }
+        for c in s.chars() {
+            match c {
+                '&' => self.buf.push_str("&amp;"),
+                '<' => self.buf.push_str("&lt;"),
+                '>' => self.buf.push_str("&gt;"),
+                c => self.buf.push(c),
+            }
+        }
+
+        self.last_style = style;
+    }
+    fn build(mut self) -> String {
+        self.close();
+        self.buf
+    }
+}
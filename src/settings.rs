@@ -0,0 +1,61 @@
+//! Settings that can be changed per guild and are kept on disk.
+
+use std::{collections::HashMap, fs, io};
+
+use serde::{Deserialize, Serialize};
+use serenity::model::id::GuildId;
+use serenity::prelude::TypeMapKey;
+
+const SETTINGS_FILE: &str = "guild_settings.json";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildSettings {
+    /// Results longer than this many messages are sent as a file instead.
+    /// 0 means never sending them as a file.
+    pub attachment_threshold: usize,
+    /// How many minutes a word game lasts. 0 means it goes on until it is ended.
+    pub word_game_minutes: u64,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        GuildSettings {
+            attachment_threshold: 3,
+            word_game_minutes: 3,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Settings {
+    guilds: HashMap<GuildId, GuildSettings>,
+}
+
+impl TypeMapKey for Settings {
+    type Value = Self;
+}
+
+impl Settings {
+    /// Loads the settings from disk, or uses the defaults if there are none
+    pub fn load() -> Self {
+        match fs::read_to_string(SETTINGS_FILE) {
+            Ok(s) => serde_json::from_str(&s).unwrap_or_else(|e| {
+                eprintln!("Could not read {}, using default settings: {}", SETTINGS_FILE, e);
+                Settings::default()
+            }),
+            Err(_) => Settings::default(),
+        }
+    }
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(SETTINGS_FILE, serde_json::to_string_pretty(self)?)
+    }
+
+    /// The settings for the guild, where messages outside of guilds get the defaults
+    pub fn guild(&self, guild_id: Option<GuildId>) -> GuildSettings {
+        guild_id.and_then(|id| self.guilds.get(&id)).copied().unwrap_or_default()
+    }
+    pub fn guild_mut(&mut self, guild_id: GuildId) -> &mut GuildSettings {
+        self.guilds.entry(guild_id).or_default()
+    }
+}
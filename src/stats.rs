@@ -0,0 +1,240 @@
+//! Results of word games kept on disk for leaderboards and player statistics.
+
+use std::{collections::{BTreeMap, HashMap}, fs, io, str::FromStr};
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serenity::model::id::{GuildId, UserId};
+use serenity::prelude::TypeMapKey;
+
+use crate::wordgame::Seed;
+
+const STATS_FILE: &str = "word_game_stats.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How one player did in one game
+pub struct GameRecord {
+    pub time: DateTime<Utc>,
+    pub points: u128,
+    pub letters: u64,
+    pub words: u32,
+    pub longest_word: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    AllTime,
+    /// The current calendar month
+    Month,
+    /// The current ISO week, starting on Monday
+    Week,
+}
+
+impl FromStr for Period {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.to_lowercase() {
+            "all" | "alltime" | "alt" => Ok(Period::AllTime),
+            "month" | "mánaður" | "manadur" => Ok(Period::Month),
+            "week" | "vika" => Ok(Period::Week),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Period {
+    /// Whether the time is within the period that `now` is in
+    pub fn contains(self, time: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        match self {
+            Period::AllTime => true,
+            Period::Month => (time.year(), time.month()) == (now.year(), now.month()),
+            Period::Week => time.iso_week() == now.iso_week(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub games: usize,
+    pub best_game: u128,
+    pub longest_word: String,
+    pub average_points: u128,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The first game of a player in a daily challenge
+pub struct DailyRecord {
+    /// The name of the player, since they might not be in the guild it is shown in
+    pub name: String,
+    pub points: u128,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WordGameStats {
+    guilds: HashMap<GuildId, HashMap<UserId, Vec<GameRecord>>>,
+    /// Results of the daily challenges across all guilds
+    #[serde(default)]
+    daily: BTreeMap<NaiveDate, HashMap<UserId, DailyRecord>>,
+    /// The seeds of the daily challenges, so the board stays the same all day if the word list changes
+    #[serde(default)]
+    daily_seeds: BTreeMap<NaiveDate, String>,
+}
+
+impl TypeMapKey for WordGameStats {
+    type Value = Self;
+}
+
+impl WordGameStats {
+    /// Loads the stats from disk, or starts with none if there are none
+    pub fn load() -> Self {
+        match fs::read_to_string(STATS_FILE) {
+            Ok(s) => serde_json::from_str(&s).unwrap_or_else(|e| {
+                eprintln!("Could not read {}, starting without stats: {}", STATS_FILE, e);
+                WordGameStats::default()
+            }),
+            Err(_) => WordGameStats::default(),
+        }
+    }
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(STATS_FILE, serde_json::to_string(self)?)
+    }
+
+    pub fn record(&mut self, guild_id: GuildId, user: UserId, record: GameRecord) {
+        self.guilds.entry(guild_id).or_default().entry(user).or_default().push(record);
+    }
+
+    /// Records a daily challenge game, keeping only the first game of each player
+    /// since the board is known after that
+    pub fn record_daily(&mut self, date: NaiveDate, user: UserId, record: DailyRecord) {
+        self.daily.entry(date).or_default().entry(user).or_insert(record);
+    }
+    /// Keeps the seed as the one of the daily challenge of the day,
+    /// giving the seed that was kept before instead if there is one
+    pub fn keep_daily_seed(&mut self, date: NaiveDate, seed: Seed) -> Seed {
+        let kept = self.daily_seeds.entry(date).or_insert_with(|| seed.to_string());
+        kept.parse().unwrap_or(seed)
+    }
+    /// The seed kept for the daily challenge of the day
+    pub fn daily_seed(&self, date: NaiveDate) -> Option<Seed> {
+        self.daily_seeds.get(&date)?.parse().ok()
+    }
+    pub fn has_played_daily(&self, date: NaiveDate, user: UserId) -> bool {
+        self.daily.get(&date).is_some_and(|day| day.contains_key(&user))
+    }
+    /// The players of the daily challenge of the day ranked by their game, best first
+    pub fn daily_leaderboard(&self, date: NaiveDate) -> Vec<(UserId, &DailyRecord)> {
+        let mut board: Vec<_> = self.daily.get(&date).into_iter().flatten().map(|(&u, r)| (u, r)).collect();
+
+        board.sort_by(|(ua, a), (ub, b)| b.points.cmp(&a.points).then(ua.cmp(ub)));
+        board
+    }
+
+    /// The players of the guild ranked by their total points in the period, best first
+    pub fn leaderboard(&self, guild_id: GuildId, period: Period, now: DateTime<Utc>) -> Vec<(UserId, u128)> {
+        let mut board: Vec<_> = self.guilds.get(&guild_id).into_iter()
+            .flatten()
+            .filter_map(|(&user, records)| {
+                let mut records = records.iter().filter(|r| period.contains(r.time, now)).peekable();
+                records.peek()?;
+                Some((user, records.map(|r| r.points).sum::<u128>()))
+            })
+            .collect();
+
+        board.sort_by(|(ua, a), (ub, b)| b.cmp(a).then(ua.cmp(ub)));
+        board
+    }
+
+    /// Statistics of all the games the user has played in the guild
+    pub fn player(&self, guild_id: GuildId, user: UserId) -> Option<PlayerStats> {
+        let records = self.guilds.get(&guild_id)?.get(&user).filter(|r| !r.is_empty())?;
+
+        let total: u128 = records.iter().map(|r| r.points).sum();
+        let longest_word = records.iter()
+            .map(|r| &r.longest_word)
+            .fold("", |longest, w| if w.chars().count() > longest.chars().count() { w } else { longest });
+
+        Some(PlayerStats {
+            games: records.len(),
+            best_game: records.iter().map(|r| r.points).max().unwrap_or(0),
+            longest_word: longest_word.to_owned(),
+            average_points: total / records.len() as u128,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+    use serenity::model::id::{GuildId, UserId};
+
+    use super::{DailyRecord, GameRecord, Period, PlayerStats, WordGameStats};
+    use crate::wordgame::Seed;
+
+    const GUILD: GuildId = GuildId::new(1);
+    const ANNA: UserId = UserId::new(10);
+    const JOGVAN: UserId = UserId::new(11);
+
+    fn date(month: u32, day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap()
+    }
+    fn record(time: DateTime<Utc>, points: u128, longest_word: &str) -> GameRecord {
+        GameRecord { time, points, letters: 0, words: 0, longest_word: longest_word.to_owned() }
+    }
+
+    fn stats() -> WordGameStats {
+        let mut stats = WordGameStats::default();
+        stats.record(GUILD, ANNA, record(date(4, 30), 20, "hestur"));
+        stats.record(GUILD, ANNA, record(date(5, 13), 4, "ró"));
+        stats.record(GUILD, JOGVAN, record(date(5, 2), 9, "seyður"));
+        stats.record(GUILD, JOGVAN, record(date(5, 14), 1, "ás"));
+        stats
+    }
+
+    #[test]
+    fn leaderboards_by_period() {
+        let stats = stats();
+        // Wednesday
+        let now = date(5, 15);
+
+        assert_eq!(stats.leaderboard(GUILD, Period::AllTime, now), vec![(ANNA, 24), (JOGVAN, 10)]);
+        assert_eq!(stats.leaderboard(GUILD, Period::Month, now), vec![(JOGVAN, 10), (ANNA, 4)]);
+        assert_eq!(stats.leaderboard(GUILD, Period::Week, now), vec![(ANNA, 4), (JOGVAN, 1)]);
+        assert_eq!(stats.leaderboard(GUILD, Period::Week, date(6, 1)), vec![]);
+        assert_eq!(stats.leaderboard(GuildId::new(2), Period::AllTime, now), vec![]);
+    }
+    #[test]
+    fn player_stats() {
+        let stats = stats();
+
+        assert_eq!(stats.player(GUILD, ANNA), Some(PlayerStats {
+            games: 2,
+            best_game: 20,
+            longest_word: "hestur".to_owned(),
+            average_points: 12,
+        }));
+        assert_eq!(stats.player(GUILD, UserId::new(12)), None);
+    }
+    #[test]
+    fn daily_keeps_first_game() {
+        let mut stats = WordGameStats::default();
+        let day = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        let daily = |name: &str, points| DailyRecord { name: name.to_owned(), points };
+
+        stats.record_daily(day, ANNA, daily("anna", 5));
+        // Playing the board again, now knowing the words, doesn't count
+        stats.record_daily(day, ANNA, daily("anna", 30));
+        stats.record_daily(day, JOGVAN, daily("jógvan", 8));
+        stats.record_daily(day.succ_opt().unwrap(), ANNA, daily("anna", 100));
+
+        let board: Vec<_> = stats.daily_leaderboard(day).into_iter().map(|(u, r)| (u, r.points)).collect();
+        assert_eq!(board, vec![(JOGVAN, 8), (ANNA, 5)]);
+        assert!(stats.has_played_daily(day, ANNA));
+        assert!(!stats.has_played_daily(day, UserId::new(12)));
+
+        let seed: Seed = "4d1".parse().unwrap();
+        assert_eq!(stats.keep_daily_seed(day, seed), seed);
+        assert_eq!(stats.keep_daily_seed(day, "4d2".parse().unwrap()), seed);
+        assert_eq!(stats.daily_seed(day), Some(seed));
+        assert_eq!(stats.daily_seed(day.succ_opt().unwrap()), None);
+    }
+}
@@ -1,9 +1,12 @@
 #![warn(missing_docs)]
 //! Module with various useful things for manipulating text for use with Discord.
 
+use serenity::builder::CreateEmbed;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 /// A dictionary entry from Grunnmanuskriptet
 pub struct Entry {
+    id: Option<u32>,
     word: String,
     class: String,
     body: String,
@@ -30,7 +33,16 @@ impl Entry {
         body = body.replace("&dagger;", "†");
 
         Entry {
-            word, class, body
+            id: None, word, class, body
+        }
+    }
+
+    #[inline]
+    /// Sets the id used to look the entry up on its own.
+    pub fn with_id(self, id: u32) -> Self {
+        Entry {
+            id: Some(id),
+            .. self
         }
     }
 }
@@ -39,18 +51,44 @@ use std::fmt::{self, Display};
 
 impl Display for Entry {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Entry{word, class, body} = self;
+        let Entry{id, word, class, body} = self;
 
-        write!(f, "**{}** _{}_{}{}", word, class, if body.len() > 20 {"\n"} else {": "}, body)
+        write!(f, "**{}** _{}_", word, class)?;
+        if let Some(id) = id {
+            write!(f, " `{}`", id)?;
+        }
+        write!(f, "{}{}", if body.len() > 20 {"\n"} else {": "}, body)
     }
 }
 
 
 const MSG_LIMIT: usize = 2000;
+/// Room left in each message for closing and reopening markdown when a message is split
+const MARKDOWN_RESERVE: usize = 48;
+/// Longest code block language that is kept when reopening a code block in the next message
+const MAX_CODE_BLOCK_LANG: usize = 16;
+
+/// A collection of chunks of text that a [`BunchBuilder`] can fill,
+/// where each chunk has a limit to how long it can be.
+pub trait Bunch {
+    /// Makes a new bunch with one empty chunk
+    fn new() -> Self;
+    /// The chunk currently being filled
+    fn last_mut(&mut self) -> &mut String;
+    /// How many characters the current chunk can hold in total
+    fn limit(&self) -> usize;
+    /// Begins a new chunk after the current one
+    fn push_chunk(&mut self);
+    /// All the chunks in order
+    fn chunks_mut(&mut self) -> Vec<&mut String>;
+    /// Called when the bunch is built with all the text as it was added, before it was split up,
+    /// and the warnings about where it had to be split
+    fn finish(&mut self, _source: String, _warnings: Vec<SplitWarning>) {}
+}
 
 #[derive(Debug, Default, Clone)]
 /// A collection of strings that each are below 2000 characters
-/// so that they can be sent as each their own Discord message
+/// so that they can be sent in Discord messages, as grouped by [`EmbedBunch::messages`]
 pub struct MsgBunch {
     /// The strings
     /// 
@@ -58,18 +96,475 @@ pub struct MsgBunch {
     /// 
     /// It's a logic error to make any of these strings bigger than 2000
     pub messages: Vec<String>,
+    /// All the text without the markdown that was added where it was split,
+    /// for when it is sent in one piece instead
+    pub source: String,
+    /// Sections that had to be split somewhere else than where they were supposed to
+    pub warnings: Vec<SplitWarning>,
 }
 
-impl MsgBunch {
+impl Bunch for MsgBunch {
     fn new() -> Self {
         MsgBunch {
-            messages: vec![String::with_capacity(MSG_LIMIT)]
+            messages: vec![String::with_capacity(MSG_LIMIT)],
+            source: String::new(),
+            warnings: Vec::new(),
+        }
+    }
+    #[inline]
+    fn last_mut(&mut self) -> &mut String {
+        self.messages.last_mut().unwrap()
+    }
+    #[inline(always)]
+    fn limit(&self) -> usize {
+        MSG_LIMIT
+    }
+    #[inline]
+    fn push_chunk(&mut self) {
+        self.messages.push(String::with_capacity(MSG_LIMIT));
+    }
+    #[inline]
+    fn chunks_mut(&mut self) -> Vec<&mut String> {
+        self.messages.iter_mut().collect()
+    }
+    fn finish(&mut self, source: String, warnings: Vec<SplitWarning>) {
+        self.source = source;
+        self.warnings = warnings;
+    }
+}
+
+impl MsgBunch {
+    /// Whether a section had to be split in the middle of a word
+    pub fn has_hard_split(&self) -> bool {
+        self.warnings.contains(&SplitWarning::HardSplit)
+    }
+    /// Adds a line at the end of the last message, or in a message of its own if there is no room
+    pub fn push_note(&mut self, note: &str) {
+        match self.messages.last_mut() {
+            Some(last) if last.chars().count() + note.chars().count() < MSG_LIMIT => {
+                last.push('\n');
+                last.push_str(note);
+            }
+            _ => self.messages.push(note.to_owned()),
+        }
+    }
+}
+
+const EMBED_TITLE_LIMIT: usize = 256;
+const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+const EMBED_FIELD_LIMIT: usize = 1024;
+const EMBED_FIELDS: usize = 25;
+const EMBED_TOTAL_LIMIT: usize = 6000;
+/// How many embeds Discord allows in one message, which also share [`EMBED_TOTAL_LIMIT`]
+const EMBEDS_PER_MESSAGE: usize = 10;
+/// Discord doesn't allow empty field names, so a zero width space is used instead
+const EMPTY_FIELD_NAME: &str = "\u{200b}";
+
+#[derive(Debug, Default, Clone)]
+/// The contents of one embed, where text goes into the description first and then into fields
+pub struct Embed {
+    /// Title of the embed, only set on the first embed of a bunch
+    pub title: Option<String>,
+    /// The description of at most 4096 characters
+    pub description: String,
+    /// The values of the fields of at most 1024 characters each.
+    /// The fields have no names.
+    pub fields: Vec<String>,
+}
+
+impl Embed {
+    /// The name that each field should be given
+    pub const FIELD_NAME: &'static str = EMPTY_FIELD_NAME;
+
+    /// Whether there is nothing to show in the embed
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_empty() && self.fields.is_empty()
+    }
+    /// The number of characters Discord counts towards [`EMBED_TOTAL_LIMIT`]
+    pub fn len(&self) -> usize {
+        let len = |s: &str| s.chars().count();
+
+        self.title.as_deref().map_or(0, len)
+            + len(&self.description)
+            + self.fields.iter().map(|f| len(Self::FIELD_NAME) + len(f)).sum::<usize>()
+    }
+    /// Makes the embed ready to be sent
+    pub fn to_create_embed(&self) -> CreateEmbed {
+        let mut ce = CreateEmbed::new();
+        if !self.description.is_empty() {
+            ce = ce.description(&self.description);
+        }
+        if let Some(title) = &self.title {
+            ce = ce.title(title);
+        }
+        ce.fields(self.fields.iter().map(|value| (Self::FIELD_NAME, value, false)))
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+/// A collection of embeds that each stay within Discord's limits for embeds
+/// so that they can be sent in Discord messages, as grouped by [`EmbedBunch::messages`]
+pub struct EmbedBunch {
+    /// The embeds
+    /// 
+    /// ## Note:
+    /// 
+    /// It's a logic error to make any of these embeds exceed the limits
+    pub embeds: Vec<Embed>,
+}
+
+impl EmbedBunch {
+    /// Characters used in the last embed before the current chunk,
+    /// counting the room needed for balancing markdown in the chunks before it
+    fn used_before_chunk(&self) -> usize {
+        let embed = self.embeds.last().unwrap();
+        let title = embed.title.as_ref().map(|t| t.chars().count()).unwrap_or(0);
+
+        match embed.fields.split_last() {
+            None => title,
+            Some((_, fields)) => {
+                title
+                    + embed.description.chars().count() + MARKDOWN_RESERVE
+                    + fields.iter().map(|f| EMPTY_FIELD_NAME.chars().count() + f.chars().count() + MARKDOWN_RESERVE).sum::<usize>()
+                    + EMPTY_FIELD_NAME.chars().count()
+            }
         }
     }
+    /// Groups the embeds in order into as few Discord messages as their limits allow
+    pub fn messages(&self) -> Vec<&[Embed]> {
+        let mut messages = Vec::new();
+        let (mut start, mut total) = (0, 0);
+
+        for (i, embed) in self.embeds.iter().enumerate() {
+            if i > start && (i - start == EMBEDS_PER_MESSAGE || total + embed.len() > EMBED_TOTAL_LIMIT) {
+                messages.push(&self.embeds[start..i]);
+                start = i;
+                total = 0;
+            }
+            total += embed.len();
+        }
+        if start < self.embeds.len() {
+            messages.push(&self.embeds[start..]);
+        }
+        messages
+    }
+}
+
+impl Bunch for EmbedBunch {
+    fn new() -> Self {
+        EmbedBunch {
+            embeds: vec![Embed::default()],
+        }
+    }
+    fn last_mut(&mut self) -> &mut String {
+        let embed = self.embeds.last_mut().unwrap();
+        match embed.fields.last_mut() {
+            Some(field) => field,
+            None => &mut embed.description,
+        }
+    }
+    fn limit(&self) -> usize {
+        let chunk_limit = if self.embeds.last().unwrap().fields.is_empty() {
+            EMBED_DESCRIPTION_LIMIT
+        } else {
+            EMBED_FIELD_LIMIT
+        };
+
+        chunk_limit.min(EMBED_TOTAL_LIMIT.saturating_sub(self.used_before_chunk()))
+    }
+    fn push_chunk(&mut self) {
+        let chunk = self.last_mut().chars().count();
+        let used = self.used_before_chunk() + chunk + MARKDOWN_RESERVE + EMPTY_FIELD_NAME.chars().count();
+        let embed = self.embeds.last_mut().unwrap();
+
+        // Fields are only added if they have room to be filled completely
+        if embed.fields.len() < EMBED_FIELDS && used + EMBED_FIELD_LIMIT <= EMBED_TOTAL_LIMIT {
+            embed.fields.push(String::new());
+        } else {
+            self.embeds.push(Embed::default());
+        }
+    }
+    fn chunks_mut(&mut self) -> Vec<&mut String> {
+        self.embeds.iter_mut().flat_map(|e| std::iter::once(&mut e.description).chain(&mut e.fields)).collect()
+    }
+    /// Leaves out the chunks that only have whitespace, since Discord doesn't allow empty fields
+    fn finish(&mut self, _source: String, _warnings: Vec<SplitWarning>) {
+        for embed in &mut self.embeds {
+            if embed.description.trim().is_empty() {
+                embed.description.clear();
+            }
+            embed.fields.retain(|f| !f.trim().is_empty());
+        }
+        self.embeds.retain(|e| !e.is_empty());
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Markdown {
+    Bold,
+    Underline,
+    Italics,
+    ItalicsStar,
+    Strikethrough,
+    Spoiler,
+    Code,
+    CodeBlock,
+}
+
+impl Markdown {
+    fn marker(self) -> &'static str {
+        match self {
+            Markdown::Bold => "**",
+            Markdown::Underline => "__",
+            Markdown::Italics => "_",
+            Markdown::ItalicsStar => "*",
+            Markdown::Strikethrough => "~~",
+            Markdown::Spoiler => "||",
+            Markdown::Code => "`",
+            Markdown::CodeBlock => "```",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The markdown constructs that are open at some point in a text
+struct MarkdownState {
+    /// In the order they were opened
+    open: Vec<Markdown>,
+    /// Language of the code block, if one is open
+    code_block_lang: String,
+}
+
+impl MarkdownState {
+    fn is_open(&self, md: Markdown) -> bool {
+        self.open.contains(&md)
+    }
+    fn toggle(&mut self, md: Markdown) {
+        match self.open.iter().rposition(|&m| m == md) {
+            Some(i) => {
+                self.open.remove(i);
+            }
+            None => self.open.push(md),
+        }
+    }
+    fn in_code(&self) -> bool {
+        self.is_open(Markdown::Code) || self.is_open(Markdown::CodeBlock)
+    }
+
+    /// Goes through `s` and returns the state right before each of the given byte offsets,
+    /// which have to be in ascending order.
+    fn scan(s: &str, offsets: &[usize]) -> Vec<Self> {
+        let chars: Vec<(usize, char)> = s.char_indices().collect();
+        let mut states = Vec::with_capacity(offsets.len());
+        let mut offsets = offsets.iter().peekable();
+        let mut state = MarkdownState::default();
+
+        let mut i = 0;
+        while let Some(&(pos, c)) = chars.get(i) {
+            while offsets.next_if(|&&o| o <= pos).is_some() {
+                states.push(state.clone());
+            }
+
+            let rest = &s[pos..];
+            let prev = i.checked_sub(1).map(|j| chars[j].1);
+            let next = chars.get(i + 1).map(|&(_, c)| c);
+
+            let md = if c == '\\' && !state.in_code() {
+                // Skip the escaped character
+                i += 2;
+                continue
+            } else if rest.starts_with("```") && !state.is_open(Markdown::Code) {
+                state.code_block_lang.clear();
+                if !state.is_open(Markdown::CodeBlock) {
+                    let lang = rest[3..].split('\n').next().unwrap_or("");
+                    if rest[3..].contains('\n') && lang.chars().count() <= MAX_CODE_BLOCK_LANG && lang.chars().all(|c| c.is_alphanumeric() || c == '+' || c == '-') {
+                        state.code_block_lang.push_str(lang);
+                    }
+                }
+                Some(Markdown::CodeBlock)
+            } else if state.is_open(Markdown::CodeBlock) {
+                None
+            } else if c == '`' {
+                Some(Markdown::Code)
+            } else if state.is_open(Markdown::Code) {
+                None
+            } else if rest.starts_with("**") {
+                Some(Markdown::Bold)
+            } else if rest.starts_with("__") {
+                Some(Markdown::Underline)
+            } else if rest.starts_with("~~") {
+                Some(Markdown::Strikethrough)
+            } else if rest.starts_with("||") {
+                Some(Markdown::Spoiler)
+            } else if c == '_' {
+                // Underscores only count at the edges of words
+                let applies = if state.is_open(Markdown::Italics) {
+                    !next.is_some_and(char::is_alphanumeric)
+                } else {
+                    !prev.is_some_and(char::is_alphanumeric) && next.is_some_and(|c| !c.is_whitespace())
+                };
+                applies.then_some(Markdown::Italics)
+            } else if c == '*' {
+                let applies = if state.is_open(Markdown::ItalicsStar) {
+                    prev.is_some_and(|c| !c.is_whitespace())
+                } else {
+                    next.is_some_and(|c| !c.is_whitespace())
+                };
+                applies.then_some(Markdown::ItalicsStar)
+            } else {
+                None
+            };
+
+            match md {
+                Some(md) => {
+                    state.toggle(md);
+                    i += md.marker().chars().count();
+                }
+                None => i += 1,
+            }
+        }
+        for _ in offsets {
+            states.push(state.clone());
+        }
+
+        states
+    }
+
+    /// Opens the constructs again at the beginning of a message
+    fn reopen(&self, msg: &mut String) {
+        let mut openers = String::new();
+        for &md in &self.open {
+            openers.push_str(md.marker());
+            if md == Markdown::CodeBlock {
+                openers.push_str(&self.code_block_lang);
+                openers.push('\n');
+            }
+        }
+
+        // Styles don't apply if they are followed by whitespace,
+        // but whitespace in code blocks has to be kept
+        let index = if self.is_open(Markdown::CodeBlock) {
+            0
+        } else {
+            msg.len() - msg.trim_start().len()
+        };
+        msg.insert_str(index, &openers);
+    }
+
+    /// Closes the constructs at the end of a message
+    fn close(&self, msg: &mut String) {
+        let mut closers = String::new();
+        for &md in self.open.iter().rev() {
+            if md == Markdown::CodeBlock && !msg.ends_with('\n') {
+                closers.push('\n');
+            }
+            closers.push_str(md.marker());
+        }
+
+        let index = if self.is_open(Markdown::CodeBlock) {
+            msg.len()
+        } else {
+            msg.trim_end().len()
+        };
+        msg.insert_str(index, &closers);
+    }
+}
+
+/// Closes any markdown still open at the end of each message and opens it again in the next one
+fn balance_markdown(messages: &mut [&mut String]) {
+    let mut ends = Vec::with_capacity(messages.len());
+    let mut joined = String::new();
+    for msg in messages.iter() {
+        joined.push_str(msg);
+        ends.push(joined.len());
+    }
+
+    let mut states = MarkdownState::scan(&joined, &ends);
+
+    // A message starting with the end of a code block would otherwise get an empty code block,
+    // so the end is moved to the message before instead
+    for (i, state) in states.iter_mut().enumerate().take(messages.len().saturating_sub(1)) {
+        if state.is_open(Markdown::CodeBlock) && messages[i + 1].starts_with("```") {
+            messages[i + 1].replace_range(..3, "");
+            messages[i].push_str("```");
+            state.toggle(Markdown::CodeBlock);
+        }
+    }
+
+    let mut start_state = MarkdownState::default();
+
+    for (msg, end_state) in messages.iter_mut().zip(states) {
+        // Discord doesn't send messages with only whitespace anyway
+        if !msg.trim().is_empty() {
+            start_state.reopen(msg);
+            end_state.close(msg);
+        }
+        start_state = end_state;
+    }
+}
+
+/// Whether the character is part of a markdown marker, that shouldn't be split in the middle of
+fn is_marker_char(c: char) -> bool {
+    matches!(c, '*' | '_' | '~' | '|' | '`' | '\\')
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A section that was too big for one message and couldn't be split where it was supposed to
+pub enum SplitWarning {
+    /// None of the characters to split at were found, so it was split at whitespace
+    SplitAtWhitespace,
+    /// There was no whitespace either, so it was split in the middle of a word
+    HardSplit,
+}
+
+/// The byte index to split `s` at so that at most `chars` characters come before it,
+/// trying not to split next to a markdown marker.
+///
+/// The index is only 0 if `allow_empty` is set.
+fn hard_split_index(s: &str, chars: usize, allow_empty: bool) -> usize {
+    let limit_index = s.char_indices().nth(chars).map(|(i, _)| i).unwrap_or(s.len());
+    let mut index = limit_index;
+
+    // Don't split next to a markdown marker, so it doesn't end up being closed and reopened wrongly
+    while let (Some(before), Some(after)) = (s[..index].chars().next_back(), s[index..].chars().next()) {
+        if !(is_marker_char(before) || is_marker_char(after)) {
+            break;
+        }
+        index -= before.len_utf8();
+    }
+
+    if index == 0 && !allow_empty {
+        // Every character might be a marker, so just make sure something is split off
+        limit_index.max(s.chars().next().map(char::len_utf8).unwrap_or(0))
+    } else {
+        index
+    }
+}
+
+/// Finds the byte index to split a section at so that at most `limit` characters come before it.
+///
+/// It is split right after the last character that `f` accepts,
+/// falling back to whitespace and then to [`hard_split_index`].
+fn find_split<F: FnMut(char) -> bool>(s: &str, limit: usize, mut f: F) -> (usize, Option<SplitWarning>) {
+    let split_after = |f: &mut dyn FnMut(char) -> bool| {
+        s.char_indices()
+            .take(limit)
+            .filter(|&(i, c)| f(c) && !s[i + c.len_utf8()..].starts_with(is_marker_char))
+            .last()
+            .map(|(i, c)| i + c.len_utf8())
+    };
+
+    if let Some(index) = split_after(&mut f) {
+        (index, None)
+    } else if let Some(index) = split_after(&mut char::is_whitespace) {
+        (index, Some(SplitWarning::SplitAtWhitespace))
+    } else {
+        (hard_split_index(s, limit, false), Some(SplitWarning::HardSplit))
+    }
 }
 
 #[derive(Debug)]
-/// Builder for an [`MsgBunch`]
+/// Builder for a [`Bunch`], such as an [`MsgBunch`] or an [`EmbedBunch`]
 /// 
 /// Has functionality that can be used to help not
 /// ruining formatting across multiple messages
@@ -79,30 +574,51 @@ impl MsgBunch {
 /// The message builder will instead try its best to only split between sections.
 /// Although if a section is too big, you can specify how it should try that section,
 /// or - by default - it will try to only split at punctuation or parentheses.
-pub struct MsgBunchBuilder {
+///
+/// Markdown that is still open where a message is split, such as bold text or a code block,
+/// is closed at the end of that message and opened again in the next one.
+pub struct BunchBuilder<B> {
     /// The partial bunch being built
-    pub inner: MsgBunch,
+    /// 
+    /// Markdown is not balanced across the messages until the bunch is built.
+    pub inner: B,
     chars_num: usize, 
     no_split_section: Option<(String, usize)>,
+    warnings: Vec<SplitWarning>,
 }
 
-impl Default for MsgBunchBuilder {
+/// Builder for an [`MsgBunch`]
+pub type MsgBunchBuilder = BunchBuilder<MsgBunch>;
+/// Builder for an [`EmbedBunch`]
+pub type EmbedBunchBuilder = BunchBuilder<EmbedBunch>;
+
+impl<B: Bunch> Default for BunchBuilder<B> {
     #[inline(always)]
     fn default() -> Self {
-        MsgBunchBuilder::new()
+        BunchBuilder::new()
+    }
+}
+
+impl EmbedBunchBuilder {
+    /// Makes a new empty bunch of embeds where the first embed has the given title
+    /// 
+    /// The title is cut off at 256 characters.
+    pub fn with_title<S: AsRef<str>>(title: S) -> Self {
+        let mut builder = Self::new();
+        builder.inner.embeds[0].title = Some(title.as_ref().chars().take(EMBED_TITLE_LIMIT).collect());
+        builder
     }
 }
 
-impl MsgBunchBuilder {
+impl<B: Bunch> BunchBuilder<B> {
     #[inline]
     /// Makes a new empty bunch
-    /// 
-    /// Allocates 2000 bytes of space
     pub fn new() -> Self {
-        MsgBunchBuilder {
-            inner: MsgBunch::new(),
+        BunchBuilder {
+            inner: B::new(),
             chars_num: 0,
             no_split_section: None,
+            warnings: Vec::new(),
         }
     }
 
@@ -110,30 +626,28 @@ impl MsgBunchBuilder {
     /// If the builder is currently in a section
     /// it will add to that section in order to manage where messages are split.
     pub fn add_string<S: AsRef<str>>(&mut self, s: S) -> &mut Self {
-        let string_to_add = s.as_ref();
+        let mut string_to_add = s.as_ref();
         let string_to_add_size = string_to_add.chars().count();
 
         if let Some((no_split_section, size)) = &mut self.no_split_section {
             *size += string_to_add_size;
             no_split_section.push_str(string_to_add);
-        } else if self.chars_num + string_to_add_size > MSG_LIMIT {
-            let cur_msg = self.inner.messages.last_mut().unwrap();
-            let cur_msg_size = cur_msg.chars().count();
-
-            let (s, index) = (cur_msg_size+1..).zip(string_to_add.char_indices()).map(|(s, (i, _))| (s, i)).nth(MSG_LIMIT-cur_msg_size).unwrap();
-            debug_assert_eq!(s, MSG_LIMIT);
+            return self;
+        }
 
-            cur_msg.push_str(&string_to_add[..index]);
+        while self.chars_num + string_to_add.chars().count() > self.content_limit() {
+            let index = hard_split_index(string_to_add, self.content_limit() - self.chars_num, self.chars_num > 0);
 
-            let new_cur_msg = string_to_add[index..].to_owned();
-            let new_cur_msg_size = new_cur_msg.chars().count();
+            self.inner.last_mut().push_str(&string_to_add[..index]);
+            self.inner.push_chunk();
+            self.chars_num = 0;
 
-            self.inner.messages.push(string_to_add[index..].to_owned());
-            self.chars_num = new_cur_msg_size;
-        } else {
-            self.inner.messages.last_mut().unwrap().push_str(string_to_add);
-            self.chars_num += string_to_add_size;
+            string_to_add = &string_to_add[index..];
         }
+
+        self.inner.last_mut().push_str(string_to_add);
+        self.chars_num += string_to_add.chars().count();
+
         self
     }
 
@@ -163,23 +677,25 @@ impl MsgBunchBuilder {
     /// Ends this section trying to split at section borders, but if the section is too big
     /// uses the given callback to determine what characters to split at.
     /// 
+    /// If none of those characters are found, the section is split at whitespace,
+    /// and if there's no whitespace either, it is split wherever the message is full.
+    /// Either case is recorded as a [`SplitWarning`].
+    /// 
     /// Does nothing if no section currently has begun.
     pub fn end_section_with<F: FnMut(char) -> bool>(&mut self, mut f: F) -> &mut Self {
         if let Some((mut no_split_section, size)) = self.no_split_section.take() {
-            if self.chars_num + size > MSG_LIMIT {
-                self.chars_num = size;
-
-                let mut no_split_section_size = no_split_section.chars().count();
+            if self.chars_num + size > self.content_limit() {
+                if self.chars_num > 0 {
+                    self.inner.push_chunk();
+                }
+                let mut no_split_section_size = size;
 
                 // If the section is longer than the msg limit, we have to split it anyway
                 // using the passed function to check charactes that should allow splits
-                while no_split_section_size > MSG_LIMIT {
-                    // take(MSG_LIMIT) so that it'll panic if it doesn't find something to split at before message limit
-                    let (mut index, _) = no_split_section.char_indices().rev().skip(no_split_section_size-MSG_LIMIT).take(MSG_LIMIT).find(|(_, c)| f(*c)).unwrap();
-                    index += 1;
-
-                    while !no_split_section.is_char_boundary(index) {
-                        index += 1;
+                while no_split_section_size > self.content_limit() {
+                    let (index, warning) = find_split(&no_split_section, self.content_limit(), &mut f);
+                    if let Some(warning) = warning {
+                        self.warnings.push(warning);
                     }
 
                     let new_cur_msg = no_split_section.split_off(index);
@@ -187,12 +703,14 @@ impl MsgBunchBuilder {
                     let first_section = std::mem::replace(&mut no_split_section, new_cur_msg);
                     no_split_section_size = no_split_section.chars().count();
 
-                    self.inner.messages.push(first_section);
+                    self.inner.last_mut().push_str(&first_section);
+                    self.inner.push_chunk();
                 }
-                self.inner.messages.push(no_split_section);
+                self.chars_num = no_split_section_size;
+                self.inner.last_mut().push_str(&no_split_section);
             } else {
                 self.chars_num += size;
-                self.inner.messages.last_mut().unwrap().push_str(&no_split_section)
+                self.inner.last_mut().push_str(&no_split_section)
             }
         }
         self
@@ -221,13 +739,25 @@ impl MsgBunchBuilder {
         self
     }
 
-    #[inline]
     /// Ends whatever sections the last begun section if there was one
     /// and returns the resulting bunch.
-    pub fn build(mut self) -> MsgBunch {
+    ///
+    /// The bunch is given the warnings about sections that had to be split
+    /// somewhere else than where they were supposed to.
+    pub fn build(mut self) -> B {
         self.end_section();
+        let mut chunks = self.inner.chunks_mut();
+        let source = chunks.iter().map(|c| c.as_str()).collect();
+        balance_markdown(&mut chunks);
+        self.inner.finish(source, self.warnings);
         self.inner
     }
+
+    #[inline]
+    /// How much text can be put in the current chunk leaving room for closing markdown
+    fn content_limit(&self) -> usize {
+        self.inner.limit().saturating_sub(MARKDOWN_RESERVE)
+    }
 }
 
 /// Splits the string into three with the beginning whitespace in the 0th field
@@ -249,23 +779,10 @@ pub fn split_trim(s: &str) -> (&str, &str, &str) {
     (front_trim, text, end_trim)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::split_trim;
-    #[test]
-    fn test_split_trim() {
-        assert_eq!(split_trim("hestetest"), ("", "hestetest", ""));
-        assert_eq!(split_trim("   hest  \n\n asdg \t\n"), ("   ", "hest  \n\n asdg", " \t\n"));
-        assert_eq!(split_trim("\n"), ("", "", "\n"));
-        assert_eq!(split_trim(" "), ("", "", " "));
-    }
-}
 
-/// Turns - and 0-9 into their superscript variants.
-/// Passes other characters through without change.
-pub fn to_super(c: char) -> char {
-    match c {
-        '-' => '⁻',
+/// The superscript variant of the character if Unicode has one.
+pub fn to_super(c: char) -> Option<char> {
+    Some(match c {
         '0' => '⁰',
         '1' => '¹',
         '2' => '²',
@@ -276,14 +793,77 @@ pub fn to_super(c: char) -> char {
         '7' => '⁷',
         '8' => '⁸',
         '9' => '⁹',
-        c => c
-    }
+        '+' => '⁺',
+        '-' | '−' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'q' => '𐞥',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        'æ' => '𐞃',
+        'ð' => 'ᶞ',
+        'ə' => 'ᵊ',
+        'A' => 'ᴬ',
+        'B' => 'ᴮ',
+        'C' => 'ꟲ',
+        'D' => 'ᴰ',
+        'E' => 'ᴱ',
+        'F' => 'ꟳ',
+        'G' => 'ᴳ',
+        'H' => 'ᴴ',
+        'I' => 'ᴵ',
+        'J' => 'ᴶ',
+        'K' => 'ᴷ',
+        'L' => 'ᴸ',
+        'M' => 'ᴹ',
+        'N' => 'ᴺ',
+        'O' => 'ᴼ',
+        'P' => 'ᴾ',
+        'Q' => 'ꟴ',
+        'R' => 'ᴿ',
+        'T' => 'ᵀ',
+        'U' => 'ᵁ',
+        'V' => 'ⱽ',
+        'W' => 'ᵂ',
+        'α' => 'ᵅ',
+        'β' => 'ᵝ',
+        'γ' => 'ᵞ',
+        'δ' => 'ᵟ',
+        'ε' => 'ᵋ',
+        'θ' => 'ᶿ',
+        'ι' => 'ᶥ',
+        'φ' => 'ᵠ',
+        'χ' => 'ᵡ',
+        _ => return None,
+    })
 }
-/// Turns - and 0-9 into their subscript variants.
-/// Passes other characters through without change.
-pub fn to_sub(c: char) -> char {
-    match c {
-        '-' => '₋',
+/// The subscript variant of the character if Unicode has one.
+pub fn to_sub(c: char) -> Option<char> {
+    Some(match c {
         '0' => '₀',
         '1' => '₁',
         '2' => '₂',
@@ -294,17 +874,336 @@ pub fn to_sub(c: char) -> char {
         '7' => '₇',
         '8' => '₈',
         '9' => '₉',
-        c => c
+        '+' => '₊',
+        '-' | '−' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        'ə' => 'ₔ',
+        'β' => 'ᵦ',
+        'γ' => 'ᵧ',
+        'ρ' => 'ᵨ',
+        'φ' => 'ᵩ',
+        'χ' => 'ᵪ',
+        _ => return None,
+    })
+}
+
+/// Maps each character with `f`.
+///
+/// Runs of characters that can't be mapped are put between `open` and `close` instead,
+/// so it is still visible that they were meant to be raised or lowered.
+/// Whitespace is kept as is.
+fn to_script(src: &str, f: fn(char) -> Option<char>, open: char, close: char) -> String {
+    let mut ret = String::with_capacity(src.len());
+    let mut unmapped = String::new();
+
+    for c in src.chars() {
+        match f(c) {
+            Some(c) if unmapped.is_empty() => ret.push(c),
+            None if !c.is_whitespace() => unmapped.push(c),
+            _ => {
+                if !unmapped.is_empty() {
+                    ret.push(open);
+                    ret.push_str(&unmapped);
+                    ret.push(close);
+                    unmapped.clear();
+                }
+                ret.push(f(c).unwrap_or(c));
+            }
+        }
     }
+    if !unmapped.is_empty() {
+        ret.push(open);
+        ret.push_str(&unmapped);
+        ret.push(close);
+    }
+
+    ret
 }
 
 #[inline]
-/// Applies [`to_sup`] to each of the characters in a given string.
+/// Applies [`to_super`] to each of the characters in a given string.
+///
+/// Runs of characters without a superscript variant are put in superscript parentheses, e.g. `⁽S⁾`.
 pub fn to_superscript(src: &str) -> String {
-    src.chars().map(to_super).collect()
+    to_script(src, to_super, '⁽', '⁾')
 }
 #[inline]
 /// Applies [`to_sub`] to each of the characters in a given string.
+///
+/// Runs of characters without a subscript variant are put in subscript parentheses, e.g. `₍b₎`.
 pub fn to_subscript(src: &str) -> String {
-    src.chars().map(to_sub).collect()
-}
\ No newline at end of file
+    to_script(src, to_sub, '₍', '₎')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_trim, to_subscript, to_superscript};
+    #[test]
+    fn test_split_trim() {
+        assert_eq!(split_trim("hestetest"), ("", "hestetest", ""));
+        assert_eq!(split_trim("   hest  \n\n asdg \t\n"), ("   ", "hest  \n\n asdg", " \t\n"));
+        assert_eq!(split_trim("\n"), ("", "", "\n"));
+        assert_eq!(split_trim(" "), ("", "", " "));
+    }
+    #[test]
+    fn script_mapping() {
+        assert_eq!(to_superscript("2-a"), "²⁻ᵃ");
+        assert_eq!(to_subscript("(n+1)"), "₍ₙ₊₁₎");
+        assert_eq!(to_superscript("i mun"), "ⁱ ᵐᵘⁿ");
+    }
+    #[test]
+    fn script_fallback() {
+        assert_eq!(to_superscript("1S"), "¹⁽S⁾");
+        assert_eq!(to_superscript("XYZ 2"), "⁽XYZ⁾ ²");
+        assert_eq!(to_subscript("ab"), "ₐ₍b₎");
+        assert_eq!(to_subscript("bcd e"), "₍bcd₎ ₑ");
+    }
+    use super::{Bunch, BunchBuilder, MsgBunch, MsgBunchBuilder, EmbedBunch, SplitWarning, EmbedBunchBuilder, Embed, MarkdownState, MSG_LIMIT, EMBEDS_PER_MESSAGE};
+    use super::{EMBED_DESCRIPTION_LIMIT, EMBED_FIELD_LIMIT, EMBED_FIELDS, EMBED_TOTAL_LIMIT};
+    use proptest::prelude::*;
+    use proptest::collection::vec;
+    use proptest::sample::select;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Lines(String),
+        Str(String),
+        Section(String),
+    }
+
+    fn build<B: Bunch>(ops: &[Op]) -> B {
+        let mut mmb = BunchBuilder::new();
+        for op in ops {
+            match op {
+                Op::Lines(s) => mmb.add_lines(s),
+                Op::Str(s) => mmb.add_string(s),
+                Op::Section(s) => mmb.begin_section().add_string(s).end_section(),
+            };
+        }
+        mmb.build()
+    }
+
+    fn is_balanced(msg: &str) -> bool {
+        MarkdownState::scan(msg, &[msg.len()]).pop().unwrap() == MarkdownState::default()
+    }
+
+    fn span() -> impl Strategy<Value = String> {
+        (select(vec!["", "", "**", "__", "_", "~~", "||", "`"]), vec("[a-zæøðí]{1,12}", 1..30))
+            .prop_map(|(marker, words)| format!("{marker}{}{marker}", words.join(" ")))
+    }
+    fn line() -> impl Strategy<Value = String> {
+        vec(span(), 1..10).prop_map(|spans| spans.join(", ") + ".")
+    }
+    fn code_block() -> impl Strategy<Value = String> {
+        (select(vec!["", "rust"]), vec("[a-z |]{0,60}", 1..80))
+            .prop_map(|(lang, lines)| format!("```{lang}\n{}\n```", lines.join("\n")))
+    }
+    fn markdown() -> impl Strategy<Value = String> {
+        vec(prop_oneof![4 => line(), 1 => code_block()], 1..60).prop_map(|blocks| blocks.join("\n"))
+    }
+
+    fn embed_within_limits(embed: &Embed) -> bool {
+        let len = |s: &str| s.chars().count();
+
+        len(&embed.description) <= EMBED_DESCRIPTION_LIMIT
+            && embed.fields.len() <= EMBED_FIELDS
+            && embed.fields.iter().all(|f| len(f) <= EMBED_FIELD_LIMIT)
+            && embed.len() <= EMBED_TOTAL_LIMIT
+    }
+
+    #[test]
+    fn test_embeds_filled_in_order() {
+        let mut mmb = EmbedBunchBuilder::with_title("hestur");
+        mmb.add_lines("Eitt djór við fýra beinum.\n".repeat(500));
+        let bunch = mmb.build();
+
+        assert_eq!(bunch.embeds[0].title.as_deref(), Some("hestur"));
+        assert!(bunch.embeds[1..].iter().all(|e| e.title.is_none()));
+        assert!(bunch.embeds[0].description.chars().count() > EMBED_FIELD_LIMIT);
+        assert!(!bunch.embeds[0].fields.is_empty());
+        assert!(bunch.embeds.iter().all(embed_within_limits));
+
+        let text: String = bunch.embeds.iter().flat_map(|e| std::iter::once(&e.description).chain(&e.fields)).map(|s| &**s).collect();
+        assert_eq!(text, "Eitt djór við fýra beinum.\n".repeat(500));
+    }
+
+    #[test]
+    fn test_no_empty_embeds() {
+        let mut mmb = EmbedBunchBuilder::new();
+        mmb.add_string("\n").begin_section().add_string("orð ".repeat(2000)).end_section().add_string("\n \n");
+        let bunch = mmb.build();
+
+        assert!(!bunch.embeds.is_empty());
+        for embed in &bunch.embeds {
+            assert!(!embed.is_empty());
+            assert!(embed.description.is_empty() || !embed.description.trim().is_empty());
+            assert!(embed.fields.iter().all(|f| !f.trim().is_empty()));
+        }
+        assert!(EmbedBunchBuilder::new().build().embeds.is_empty());
+    }
+
+    #[test]
+    fn test_embeds_grouped_in_messages() {
+        let small = |s: &str| Embed { description: s.to_owned(), ..Default::default() };
+        let bunch = EmbedBunch { embeds: (0..12).map(|i| small(&i.to_string())).collect() };
+        let messages = bunch.messages();
+
+        assert_eq!(messages.iter().map(|m| m.len()).collect::<Vec<_>>(), [10, 2]);
+        assert_eq!(messages[1][1].description, "11");
+
+        let mut mmb = EmbedBunchBuilder::with_title("hestur");
+        mmb.add_string("orð ".repeat(5000));
+        let bunch = mmb.build();
+        let messages = bunch.messages();
+
+        assert_eq!(messages.concat().len(), bunch.embeds.len());
+        assert!(messages.len() > 1);
+        for message in messages {
+            assert!(message.len() <= EMBEDS_PER_MESSAGE);
+            assert!(message.iter().map(Embed::len).sum::<usize>() <= EMBED_TOTAL_LIMIT);
+        }
+        assert!(EmbedBunch::default().messages().is_empty());
+    }
+
+    #[test]
+    fn test_markdown_reopened() {
+        let mut mmb = MsgBunchBuilder::new();
+        mmb.add_string("**").add_string("a ".repeat(1500)).add_string("**");
+        let bunch = mmb.build();
+
+        assert_eq!(bunch.messages.len(), 2);
+        assert!(bunch.messages[0].starts_with("**a") && bunch.messages[0].ends_with("a** "));
+        assert!(bunch.messages[1].starts_with("**a") && bunch.messages[1].ends_with("a **"));
+    }
+
+    #[test]
+    fn test_code_block_reopened() {
+        let table = format!("```rust\n{}```\n", "| hestur | hesturin |\n".repeat(150));
+        let mut mmb = MsgBunchBuilder::new();
+        mmb.add_lines(table);
+        let bunch = mmb.build();
+
+        assert_eq!(bunch.messages.len(), 2);
+        assert!(bunch.messages[0].starts_with("```rust\n") && bunch.messages[0].ends_with("|\n```"));
+        assert!(bunch.messages[1].starts_with("```rust\n|") && bunch.messages[1].ends_with("|\n```\n"));
+    }
+
+    #[test]
+    fn test_split_fallbacks() {
+        let mut mmb = MsgBunchBuilder::new();
+        mmb.begin_section().add_string("orð ".repeat(1000)).end_section();
+        let bunch = mmb.build();
+
+        assert_eq!(bunch.warnings, [SplitWarning::SplitAtWhitespace, SplitWarning::SplitAtWhitespace]);
+        assert!(!bunch.has_hard_split());
+        assert!(bunch.messages.iter().all(|msg| msg.ends_with("orð ") && msg.chars().count() <= MSG_LIMIT));
+
+        let url = format!("https://sprotin.fo/{}", "a".repeat(5000));
+        let mut mmb = MsgBunchBuilder::new();
+        mmb.begin_section().add_string(&url).end_section_with(|_| false);
+        let mut bunch = mmb.build();
+
+        assert_eq!(bunch.warnings, [SplitWarning::HardSplit, SplitWarning::HardSplit]);
+        assert_eq!(bunch.messages.len(), 3);
+        assert_eq!(bunch.messages.concat(), url);
+
+        bunch.push_note("_note_");
+        assert_eq!(bunch.messages.len(), 3);
+        assert!(bunch.messages[2].ends_with("a\n_note_"));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn prop_messages_within_limit(ops in vec(prop_oneof![
+            "\\PC{0,3000}".prop_map(Op::Str),
+            markdown().prop_map(Op::Lines),
+        ], 1..6)) {
+            for msg in build::<MsgBunch>(&ops).messages {
+                prop_assert!(msg.chars().count() <= MSG_LIMIT);
+            }
+        }
+
+        #[test]
+        fn prop_markdown_balanced(ops in vec(prop_oneof![
+            markdown().prop_map(Op::Lines),
+            markdown().prop_map(Op::Str),
+            line().prop_map(Op::Section),
+        ], 1..6)) {
+            for msg in build::<MsgBunch>(&ops).messages {
+                prop_assert!(msg.chars().count() <= MSG_LIMIT);
+                prop_assert!(is_balanced(&msg), "unbalanced message: {:?}", msg);
+            }
+        }
+
+        #[test]
+        fn prop_embeds_within_limits(title in "\\PC{0,300}", ops in vec(prop_oneof![
+            "\\PC{0,3000}".prop_map(Op::Str),
+            markdown().prop_map(Op::Lines),
+        ], 1..10)) {
+            let mut mmb = EmbedBunchBuilder::with_title(title);
+            for op in &ops {
+                match op {
+                    Op::Lines(s) => mmb.add_lines(s),
+                    Op::Str(s) | Op::Section(s) => mmb.add_string(s),
+                };
+            }
+            let bunch: EmbedBunch = mmb.build();
+
+            for embed in &bunch.embeds {
+                prop_assert!(embed_within_limits(embed), "embed over limits: {:?}", embed);
+            }
+        }
+
+        #[test]
+        fn prop_sections_never_panic(sections in vec(("\\PC{0,5000}", any::<bool>()), 1..6)) {
+            let mut mmb = MsgBunchBuilder::new();
+            let mut emb = EmbedBunchBuilder::new();
+            for (section, split_anywhere) in &sections {
+                let split_at = |c: char| *split_anywhere && c == '.';
+                mmb.begin_section().add_string(section).end_section_with(split_at);
+                emb.begin_section().add_string(section).end_section_with(split_at);
+            }
+
+            for msg in mmb.build().messages {
+                prop_assert!(msg.chars().count() <= MSG_LIMIT);
+            }
+            for embed in emb.build().embeds {
+                prop_assert!(embed_within_limits(&embed), "embed over limits: {:?}", embed);
+            }
+        }
+
+        #[test]
+        fn prop_plain_text_unchanged(ops in vec(prop_oneof![
+            "[a-z ,.\n]{0,3000}".prop_map(Op::Str),
+            "[a-z ,.\n]{0,3000}".prop_map(Op::Lines),
+        ], 1..6)) {
+            let expected: String = ops.iter().map(|op| match op {
+                Op::Lines(s) => s.lines().map(|l| format!("{l}\n")).collect(),
+                Op::Str(s) | Op::Section(s) => s.clone(),
+            }).collect();
+
+            prop_assert_eq!(build::<MsgBunch>(&ops).messages.concat(), expected);
+        }
+    }
+}
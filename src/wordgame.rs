@@ -1,14 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
 
-use rand::rng;
+use chrono::{Datelike, NaiveDate};
+use rand::{rng, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rand::distr::Distribution;
 use rand::distr::weighted::WeightedIndex;
+use rand::seq::{IndexedRandom, SliceRandom};
 
 use serenity::model::channel::Message;
-use serenity::model::id::UserId;
+use serenity::model::id::{ChannelId, UserId};
 use serenity::prelude::TypeMapKey;
+use tokio::sync::Mutex;
 
-/// Based on distributions on Wikipedia, please replace with others at some point.
+/// Based on distributions on Wikipedia, used when the word list is too small for [`corpus_weights`].
 const LETTER_WEIGHTS: [(char, u32); 29] = [
     ('a', 9_180),
     ('á', 1_240),
@@ -45,6 +52,79 @@ fn weighted_index() -> WeightedIndex<u32> {
     WeightedIndex::new(LETTER_WEIGHTS.iter().map(|i| i.1)).unwrap()
 }
 
+/// The letters of the Faroese alphabet in order
+pub fn alphabet() -> impl Iterator<Item=char> {
+    LETTER_WEIGHTS.iter().map(|w| w.0)
+}
+
+/// How many letters the word list needs to have before its letter counts are used as weights
+const MIN_CORPUS_LETTERS: u32 = 10_000;
+
+/// Weights of the letters in [`LETTER_WEIGHTS`] counted from all the forms in the word list,
+/// or `None` if it is too small to say much
+pub fn corpus_weights(word_list: &WordList) -> Option<Vec<(char, u32)>> {
+    let mut weights: Vec<(char, u32)> = LETTER_WEIGHTS.iter().map(|&(c, _)| (c, 0)).collect();
+
+    for word in word_list.filter(|_| true) {
+        for c in word.chars() {
+            if let Some(w) = weights.iter_mut().find(|w| w.0 == c) {
+                w.1 += 1;
+            }
+        }
+    }
+
+    if weights.iter().map(|w| w.1).sum::<u32>() < MIN_CORPUS_LETTERS {
+        return None;
+    }
+    // Every letter should still be possible to get
+    for w in &mut weights {
+        w.1 = w.1.max(1);
+    }
+    Some(weights)
+}
+
+/// Faroese letter dice for Boggle-style tables, each rolled once per table
+///
+/// Tables bigger than 4×4 use the dice again from the start.
+const DICE: [[char; 6]; 16] = [
+    ['a', 'a', 'e', 'i', 'n', 'r'],
+    ['a', 'e', 'i', 'l', 'n', 's'],
+    ['a', 'i', 'n', 'r', 't', 'u'],
+    ['a', 'e', 'l', 'r', 's', 't'],
+    ['á', 'í', 'ó', 'ú', 'ý', 'æ'],
+    ['b', 'd', 'g', 'k', 'p', 'v'],
+    ['a', 'ð', 'i', 'n', 'r', 'u'],
+    ['a', 'e', 'i', 'o', 'u', 'y'],
+    ['f', 'g', 'h', 'k', 'l', 'm'],
+    ['a', 'i', 'n', 'r', 's', 't'],
+    ['j', 'k', 'm', 'n', 's', 'v'],
+    ['a', 'á', 'e', 'í', 'ó', 'ø'],
+    ['d', 'ð', 'g', 'l', 'r', 'u'],
+    ['a', 'e', 'k', 'n', 't', 'u'],
+    ['i', 'l', 'o', 's', 'ú', 'y'],
+    ['a', 'g', 'h', 'm', 'r', 't'],
+];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Generation {
+    /// Rolls the letter dice and shuffles them around the table
+    #[default]
+    Dice,
+    /// Picks each letter on its own, weighted by how common it is in the word list
+    Letters,
+}
+
+impl FromStr for Generation {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.to_lowercase() {
+            "dice" | "terningar" => Ok(Generation::Dice),
+            "letters" | "bókstavir" | "bokstavir" => Ok(Generation::Letters),
+            _ => Err(()),
+        }
+    }
+}
+
 /// Generate `n` random Faroese letters following a distribution of how common those characters are.
 pub fn gen_random_chars(n: usize) -> Vec<char> {
     let mut v = Vec::with_capacity(n);
@@ -57,128 +137,1139 @@ pub fn gen_random_chars(n: usize) -> Vec<char> {
     v
 }
 
-pub type Table = [char; 16];
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A square board of letters, or a single row of them for a rack
+pub struct Table {
+    width: usize,
+    cells: Vec<char>,
+    min_letters: u64,
+}
 
-pub fn gen_table() -> Table {
-    let mut v = [' '; 16];
-    let dist = weighted_index();
-    let mut rng = rng();
+/// The widths a table can have
+pub const TABLE_WIDTHS: [usize; 3] = [4, 5, 6];
 
-    for c in &mut v {
-        *c = LETTER_WEIGHTS[dist.sample(&mut rng)].0;
+impl Table {
+    /// Makes a table from the cells row by row
+    ///
+    /// # Panics
+    /// If the number of cells isn't `width` squared
+    pub fn new(width: usize, cells: Vec<char>) -> Self {
+        assert_eq!(cells.len(), width * width, "a table of width {} needs {} cells", width, width * width);
+        // Two letters on 4×4 and one more for each size up
+        Table { width, cells, min_letters: width as u64 - 2 }
+    }
+    /// Makes a rack of letters in one row, where words need at least `min_letters` letters
+    pub fn rack(cells: Vec<char>, min_letters: u64) -> Self {
+        Table { width: cells.len(), cells, min_letters }
+    }
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    #[inline]
+    pub fn cells(&self) -> &[char] {
+        &self.cells
+    }
+    /// The shortest a word can be
+    pub fn min_letters(&self) -> u64 {
+        self.min_letters
+    }
+    fn neighbours(&self, i: usize) -> impl Iterator<Item=usize> {
+        let width = self.width as isize;
+        let height = (self.cells.len() / self.width) as isize;
+        let (x, y) = (i as isize % width, i as isize / width);
+
+        (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+            .filter(move |&(nx, ny)| (nx, ny) != (x, y))
+            .filter(move |&(nx, ny)| (0..width).contains(&nx) && (0..height).contains(&ny))
+            .map(move |(nx, ny)| (ny * width + nx) as usize)
+    }
+}
+
+/// Generates a table by rolling the dice
+pub fn gen_dice_table<R: Rng + ?Sized>(width: usize, rng: &mut R) -> Table {
+    let mut cells: Vec<char> = DICE.iter()
+        .cycle()
+        .take(width * width)
+        .map(|die| *die.choose(rng).unwrap())
+        .collect();
+    cells.shuffle(rng);
+
+    Table::new(width, cells)
+}
+
+/// Generates a table picking each letter on its own by the given weights
+pub fn gen_weighted_table<R: Rng + ?Sized>(width: usize, weights: &[(char, u32)], rng: &mut R) -> Table {
+    let dist = WeightedIndex::new(weights.iter().map(|w| w.1)).unwrap();
+
+    let cells = (0..width * width).map(|_| weights[dist.sample(rng)].0).collect();
+    Table::new(width, cells)
+}
+
+/// How many words must be possible on a 4×4 table for it to be kept, scaled up for bigger tables
+const MIN_SOLVABLE_WORDS: usize = 20;
+/// How many tables are tried at most before giving up and using the best one
+const MAX_REROLLS: usize = 10;
+
+/// The letter weights for [`Generation::Letters`], from the word list if it is big enough
+pub fn letter_weights(word_list: &WordList) -> Vec<(char, u32)> {
+    corpus_weights(word_list).unwrap_or_else(|| LETTER_WEIGHTS.to_vec())
+}
+
+/// A number to seed the daily puzzles of the day with
+pub fn daily_number(date: NaiveDate) -> u64 {
+    // Spreads the days out so following days don't get similar streams
+    const DAILY_MIX: u64 = 0x9E37_79B9_7F4A_7C15;
+
+    (date.num_days_from_ce() as u64).wrapping_mul(DAILY_MIX)
+}
+
+/// The weights a level in [`SeedWeights`] stands for, each about √2 times the one before
+const LEVEL_WEIGHTS: [u32; 16] = [2, 3, 4, 6, 8, 11, 16, 23, 32, 45, 64, 91, 128, 181, 256, 362];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Letter weights rounded to one of the [`LEVEL_WEIGHTS`] for each letter of the alphabet,
+/// so a seed can carry the weights its table was made with
+pub struct SeedWeights([u8; LETTER_WEIGHTS.len()]);
+
+impl SeedWeights {
+    /// Rounds the weights, which are in the order of the alphabet, relative to the biggest one
+    pub fn new(weights: &[(char, u32)]) -> Self {
+        let max = weights.iter().map(|w| w.1).max().unwrap_or(1).max(1) as f64;
+        let top = *LEVEL_WEIGHTS.last().unwrap() as f64;
+        let mut levels = [0; LETTER_WEIGHTS.len()];
+
+        for (level, &(_, w)) in levels.iter_mut().zip(weights) {
+            let scaled = (w as f64 * top / max).max(1.);
+            // The closest level by ratio
+            *level = (0..LEVEL_WEIGHTS.len())
+                .min_by(|&a, &b| {
+                    let off = |l: usize| (LEVEL_WEIGHTS[l] as f64 / scaled).ln().abs();
+                    off(a).total_cmp(&off(b))
+                })
+                .unwrap() as u8;
+        }
+        SeedWeights(levels)
+    }
+    pub fn weights(&self) -> Vec<(char, u32)> {
+        alphabet().zip(self.0).map(|(c, l)| (c, LEVEL_WEIGHTS[l as usize])).collect()
+    }
+}
+
+impl Display for SeedWeights {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.iter().try_for_each(|l| write!(f, "{:x}", l))
+    }
+}
+
+impl FromStr for SeedWeights {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut levels = [0; LETTER_WEIGHTS.len()];
+        let mut digits = s.chars().map(|c| c.to_digit(16));
+
+        for level in &mut levels {
+            *level = digits.next().flatten().ok_or(())? as u8;
+        }
+        match digits.next() {
+            None => Ok(SeedWeights(levels)),
+            Some(_) => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Everything needed to make the same table again
+///
+/// Seeds of [`Generation::Letters`] tables carry their letter weights, since the weights
+/// from the word list change as it grows.
+pub struct Seed {
+    pub width: usize,
+    pub generation: Generation,
+    pub value: u64,
+    /// The weights of a [`Generation::Letters`] table
+    pub weights: Option<SeedWeights>,
+}
+
+impl Display for Seed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let generation = match self.generation {
+            Generation::Dice => 'd',
+            Generation::Letters => 'l',
+        };
+        write!(f, "{}{}{:x}", self.width, generation, self.value)?;
+        match self.weights {
+            Some(weights) => write!(f, "-{}", weights),
+            None => Ok(()),
+        }
+    }
+}
+
+impl FromStr for Seed {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let width = chars.next().and_then(|c| c.to_digit(10)).map(|w| w as usize).filter(|w| TABLE_WIDTHS.contains(w)).ok_or(())?;
+        let generation = chars.next();
+        let rest = chars.as_str();
+
+        let (generation, value, weights) = match generation {
+            Some('d') => (Generation::Dice, rest, None),
+            Some('l') => {
+                let (value, weights) = rest.split_once('-').ok_or(())?;
+                (Generation::Letters, value, Some(weights.parse()?))
+            }
+            _ => return Err(()),
+        };
+        let value = u64::from_str_radix(value, 16).map_err(|_| ())?;
+
+        Ok(Seed { width, generation, value, weights })
     }
-    v
 }
 
-#[derive(Default, Clone, Copy)]
+impl Seed {
+    /// A random seed, with the letter weights rounded to fit in it if they are needed
+    pub fn random(width: usize, generation: Generation, weights: &[(char, u32)]) -> Self {
+        let weights = match generation {
+            Generation::Dice => None,
+            Generation::Letters => Some(SeedWeights::new(weights)),
+        };
+        Seed { width, generation, value: rng().random(), weights }
+    }
+    /// The seed of the daily challenge, the same for everyone on the same day
+    ///
+    /// Seeds made from the day are tried in order until one has enough words on a Boggle board,
+    /// like in [`gen_table`]. Which one that is depends on the word list,
+    /// so the seed should be kept for the rest of the day once it is picked.
+    pub fn daily(date: NaiveDate, word_list: &WordList) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(daily_number(date));
+        let mut value = daily_number(date);
+        let mut gen = || {
+            let seed = Seed { width: TABLE_WIDTHS[0], generation: Generation::Dice, value, weights: None };
+            value = rng.random();
+            (seed.table(), seed)
+        };
+
+        if word_list.is_empty() {
+            return gen().1;
+        }
+        pick_table(gen, MIN_SOLVABLE_WORDS, Mode::Boggle, word_list).1
+    }
+    /// Makes the table of this seed
+    pub fn table(&self) -> Table {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.value);
+
+        match (self.generation, self.weights) {
+            (Generation::Letters, Some(weights)) => gen_weighted_table(self.width, &weights.weights(), &mut rng),
+            // Seeds are only made or parsed with weights for letter tables
+            _ => gen_dice_table(self.width, &mut rng),
+        }
+    }
+}
+
+/// Generates a table with the given width, rerolling tables with too few possible words
+///
+/// The seed given is the one of the table that was picked.
+/// If the word list is empty, the first table is used.
+pub fn gen_table(width: usize, generation: Generation, mode: Mode, word_list: &WordList) -> (Table, Seed) {
+    let weights = letter_weights(word_list);
+    let gen = || {
+        let seed = Seed::random(width, generation, &weights);
+        (seed.table(), seed)
+    };
+
+    if word_list.is_empty() {
+        return gen();
+    }
+
+    pick_table(gen, MIN_SOLVABLE_WORDS * width * width / 16, mode, word_list)
+}
+
+/// Takes tables from `gen` until one has at least `min_words` possible words,
+/// keeping the best one if none do within [`MAX_REROLLS`] tries
+fn pick_table(mut gen: impl FnMut() -> (Table, Seed), min_words: usize, mode: Mode, word_list: &WordList) -> (Table, Seed) {
+    let mut best = (0, None);
+    for _ in 0..MAX_REROLLS {
+        let (table, seed) = gen();
+        let words = solve(&table, mode, word_list).len();
+
+        if words >= min_words {
+            return (table, seed);
+        }
+        if best.1.is_none() || words > best.0 {
+            best = (words, Some((table, seed)));
+        }
+    }
+    best.1.unwrap()
+}
+
+#[derive(Default, Clone)]
 pub struct Points {
+    /// All the points, bonus points included
     pub points: u128,
+    /// The bonus points given at the end of the game
+    pub bonus: u128,
     pub letters: u64,
     pub words: u32,
+    pub longest_word: String,
+}
+
+/// The value of a letter for [`Scoring::Rarity`], from 1 for the most common letters up to 10
+pub fn letter_value(c: char) -> u128 {
+    let most_common = LETTER_WEIGHTS.iter().map(|w| w.1).max().unwrap();
+    let weight = LETTER_WEIGHTS.iter().find(|w| w.0 == c).map_or(1, |w| w.1);
+
+    (1 + (most_common / weight).ilog2() as u128).min(10)
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// How many points a word gives
+pub enum Scoring {
+    /// The square of how many letters longer than the shortest allowed word the word is, plus one
+    #[default]
+    Squared,
+    /// The brackets of the board game by the number of letters, where words of up to four letters give one point
+    Brackets,
+    /// The sum of the values of the letters, which are higher the rarer the letter is
+    Rarity,
+}
+
+impl FromStr for Scoring {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.to_lowercase() {
+            "squared" | "ferningur" => Ok(Scoring::Squared),
+            "brackets" | "bólkar" | "bolkar" => Ok(Scoring::Brackets),
+            "rarity" | "scrabble" | "sjaldsemi" => Ok(Scoring::Rarity),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Scoring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Scoring::Squared => write!(f, "squared length"),
+            Scoring::Brackets => write!(f, "length brackets"),
+            Scoring::Rarity => write!(f, "letter rarity"),
+        }
+    }
+}
+
+impl Scoring {
+    /// Points given for the word on the table
+    pub fn score(self, table: &Table, word: &str) -> u128 {
+        let letter_count = word.chars().count() as u64;
+        // How many letters longer than the shortest allowed word it is
+        let extra = letter_count.saturating_sub(table.min_letters());
+
+        match self {
+            Scoring::Squared => ((extra + 1) as u128).pow(2),
+            Scoring::Brackets => match letter_count {
+                ..=4 => 1,
+                5 => 2,
+                6 => 3,
+                7 => 5,
+                _ => 11,
+            },
+            Scoring::Rarity => word.chars().map(letter_value).sum(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Extra points given when the game ends
+///
+/// With a bonus, more players can find the same word and all of them get its points.
+pub enum Bonus {
+    #[default]
+    None,
+    /// The first player to find a word gets its points again
+    FirstFinder,
+    /// Players get the points again for words nobody else found
+    Unique,
+}
+
+impl FromStr for Bonus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.to_lowercase() {
+            "first" | "fyrst" => Ok(Bonus::FirstFinder),
+            "unique" | "einstakt" => Ok(Bonus::Unique),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Bonus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bonus::None => write!(f, "no bonus"),
+            Bonus::FirstFinder => write!(f, "bonus for finding a word first"),
+            Bonus::Unique => write!(f, "bonus for words nobody else found"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Words can use the letters of the table in any order
+    #[default]
+    Free,
+    /// Words must be traceable through neighbouring letters, diagonals included,
+    /// without using the same letter twice
+    Boggle,
+}
+
+impl FromStr for Mode {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match &*s.to_lowercase() {
+            "free" | "frítt" | "fritt" => Ok(Mode::Free),
+            "boggle" | "grannar" => Ok(Mode::Boggle),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Whether the word only uses letters of the table, each at most as many times as it is there
+pub fn fits_letters(table: &Table, word: &[char]) -> bool {
+    let mut letters: Vec<char> = table.cells.clone();
+    letters.sort_unstable();
+
+    word.iter().all(|c| match letters.binary_search(c) {
+        Ok(i) => {
+            letters.remove(i);
+            true
+        }
+        Err(_) => false,
+    })
 }
 
 pub struct WordGameState {
     pub table: Table,
+    /// How to make the table again, if it can be
+    pub seed: Option<Seed>,
+    /// The day of the daily challenge if this game is one
+    pub daily: Option<NaiveDate>,
+    pub mode: Mode,
+    pub scoring: Scoring,
+    pub bonus: Bonus,
+    /// Every word that has been found, sorted
     pub taken_words: Vec<String>,
+    /// The players who found each word, in the order they found it
+    pub finders: HashMap<String, Vec<UserId>>,
+    /// The cells of the last word found, if words have to follow neighbouring letters
+    pub last_path: Vec<usize>,
+    /// The rule that accepted each of the taken words
+    pub rules: HashMap<String, Rule>,
     pub guessers: HashMap<UserId, Points>,
     pub message: Message,
+    /// The user who started the game
+    pub starter: UserId,
+    /// Users who have voted to end the game early
+    pub end_votes: HashSet<UserId>,
 }
 
 pub enum GuessError {
     AlreadyGuessed,
     WrongLetters,
+    NotAdjacent,
     NotFound(String),
     TooShort,
+    /// Sprotin couldn't be asked, with the status code of why
+    LookupFailed(u16),
 }
 
 pub fn format_table(table: &Table) -> String {
-    let &[
-        a0, b0, c0, d0,
-        a1, b1, c1, d1,
-        a2, b2, c2, d2,
-        a3, b3, c3, d3,
-    ] = table;
-
-    format!(
-        "```\n{} {} {} {}\n{} {} {} {}\n{} {} {} {}\n{} {} {} {}\n```",
-        a0, b0, c0, d0,
-        a1, b1, c1, d1,
-        a2, b2, c2, d2,
-        a3, b3, c3, d3,
-    )
+    let mut s = String::from("```\n");
+
+    for row in table.cells.chunks(table.width) {
+        let row: Vec<String> = row.iter().map(char::to_string).collect();
+        s.push_str(&row.join(" "));
+        s.push('\n');
+    }
+
+    s.push_str("```");
+    s
+}
+
+/// The cells the word can be traced through, from neighbour to neighbour without reusing any
+pub fn trace_path(table: &Table, word: &[char]) -> Option<Vec<usize>> {
+    fn trace_from(table: &Table, i: usize, rest: &[char], path: &mut Vec<usize>) -> bool {
+        path.push(i);
+        let Some((&c, rest)) = rest.split_first() else {
+            return true;
+        };
+
+        let found = table.neighbours(i).any(|n| !path.contains(&n) && table.cells[n] == c && trace_from(table, n, rest, path));
+        if !found {
+            path.pop();
+        }
+
+        found
+    }
+
+    let (&first, rest) = word.split_first()?;
+    let mut path = Vec::with_capacity(word.len());
+
+    (0..table.cells.len()).find(|&i| table.cells[i] == first && trace_from(table, i, rest, &mut path)).map(|_| path)
+}
+
+/// Whether the word can be traced through neighbouring cells of the table without reusing any
+pub fn can_trace(table: &Table, word: &[char]) -> bool {
+    word.is_empty() || trace_path(table, word).is_some()
+}
+
+/// All the words in the word list that can be made on the table, sorted alphabetically
+pub fn solve(table: &Table, mode: Mode, word_list: &WordList) -> Vec<String> {
+    word_list.filter(|word| {
+        let chars: Vec<char> = word.chars().collect();
+
+        chars.len() as u64 >= table.min_letters()
+            && fits_letters(table, &chars)
+            && (mode == Mode::Free || can_trace(table, &chars))
+    })
 }
 
 impl WordGameState {
-    pub fn new(table: Table, message: Message) -> Self {
+    pub fn new(table: Table, seed: Option<Seed>, daily: Option<NaiveDate>, mode: Mode, starter: UserId, message: Message) -> Self {
         WordGameState {
+            seed,
+            daily,
+            mode,
+            scoring: Scoring::default(),
+            bonus: Bonus::default(),
             taken_words: Vec::new(),
+            finders: HashMap::new(),
+            last_path: Vec::new(),
+            rules: HashMap::new(),
             guessers: HashMap::new(),
             table,
-            message
+            message,
+            starter,
+            end_votes: HashSet::new(),
         }
     }
-    pub async fn guess_word(&mut self, user: UserId, word: String) -> Result<(), GuessError> {
-        let index_to_insert = match self.taken_words.binary_search(&word) {
-            Ok(_) => return Err(GuessError::AlreadyGuessed),
-            Err(i) => i,
+    /// Sets how words are scored, which can't be changed once a word has been found
+    pub fn with_scoring(self, scoring: Scoring, bonus: Bonus) -> Self {
+        WordGameState { scoring, bonus, ..self }
+    }
+    /// Only players who have found a word can vote, or the starter if nobody has yet
+    fn can_vote(&self, user: &UserId) -> bool {
+        self.guessers.contains_key(user) || self.guessers.is_empty() && *user == self.starter
+    }
+    /// Counts the vote to end the game and gives the number of votes and how many are needed,
+    /// or `None` if the user can't vote.
+    ///
+    /// More than half of the players have to vote for the game to end.
+    pub fn vote_end(&mut self, user: UserId) -> Option<(usize, usize)> {
+        if !self.can_vote(&user) {
+            return None;
+        }
+        self.end_votes.insert(user);
+
+        let voters = self.guessers.len().max(1);
+        let votes = self.end_votes.iter().filter(|u| self.can_vote(u)).count();
+
+        Some((votes, voters / 2 + 1))
+    }
+    /// The players ranked by points, best first
+    pub fn ranking(&self) -> Vec<(UserId, &Points)> {
+        let mut ranking: Vec<_> = self.guessers.iter().map(|(&u, p)| (u, p)).collect();
+        ranking.sort_by(|(_, a), (_, b)| b.points.cmp(&a.points).then(b.letters.cmp(&a.letters)));
+        ranking
+    }
+    pub async fn guess_word(&mut self, client: &HttpClient, word_list: &WordList, user: UserId, word: String) -> Result<(), GuessError> {
+        let taken = self.taken_words.binary_search(&word);
+        if taken.is_ok() && (self.bonus == Bonus::None || self.finders.get(&word).is_some_and(|f| f.contains(&user))) {
+            return Err(GuessError::AlreadyGuessed);
+        }
+
+        let chars: Vec<char> = word.chars().collect();
+
+        if !fits_letters(&self.table, &chars) {
+            return Err(GuessError::WrongLetters);
+        }
+        let path = match self.mode {
+            Mode::Free => Vec::new(),
+            Mode::Boggle => trace_path(&self.table, &chars).ok_or(GuessError::NotAdjacent)?,
         };
 
-        let mut letters: Vec<char> = self.table.to_vec();
-        letters.sort_unstable();
-        for c in word.chars() {
-            match letters.binary_search(&c) {
-                Ok(i) => {
-                    letters.remove(i);
+        let letter_count = chars.len() as u64;
+
+        if letter_count < self.table.min_letters() {
+            return Err(GuessError::TooShort);
+        }
+
+        // Words someone else has found are already known to be valid
+        if let Err(index_to_insert) = taken {
+            let Some(rule) = check_word(client, word_list, &word).await.map_err(GuessError::LookupFailed)? else {
+                return Err(GuessError::NotFound(word));
+            };
+            self.rules.insert(word.clone(), rule);
+            self.taken_words.insert(index_to_insert, word.clone());
+        }
+
+        let ps = self.guessers.entry(user).or_insert_with(Default::default);
+        ps.words += 1;
+        ps.letters += letter_count;
+        ps.points += self.scoring.score(&self.table, &word);
+        if letter_count as usize > ps.longest_word.chars().count() {
+            ps.longest_word.clone_from(&word);
+        }
+        self.finders.entry(word).or_default().push(user);
+        self.last_path = path;
+
+        Ok(())
+    }
+    /// Gives the bonus points, which must only be done once when the game is over
+    pub fn give_bonuses(&mut self) {
+        for (word, finders) in &self.finders {
+            let receivers = match self.bonus {
+                Bonus::None => continue,
+                Bonus::FirstFinder => &finders[..1],
+                Bonus::Unique if finders.len() == 1 => &finders[..],
+                Bonus::Unique => continue,
+            };
+            let points = self.scoring.score(&self.table, word);
+
+            for user in receivers {
+                if let Some(ps) = self.guessers.get_mut(user) {
+                    ps.bonus += points;
+                    ps.points += points;
                 }
-                Err(_) => return Err(GuessError::WrongLetters),
+            }
+        }
+    }
+    /// Finds every word in the word list that can be made on the table
+    pub fn solve(&self, word_list: &WordList) -> Solution {
+        let mut words = solve(&self.table, self.mode, word_list);
+        // Taken words are always valid, even if the list has been lost in the meantime
+        for word in &self.taken_words {
+            if let Err(i) = words.binary_search(word) {
+                words.insert(i, word.clone());
             }
         }
 
-        let letter_count = (self.table.len() - letters.len()) as u64;
+        let total_points = words.iter().map(|w| self.scoring.score(&self.table, w)).sum();
 
-        if letter_count < 2 {
-            return Err(GuessError::TooShort);
+        Solution {
+            words,
+            total_points,
+        }
+    }
+    /// Points of all the words that have been found, counting each word once and without bonuses
+    pub fn points(&self) -> u128 {
+        self.taken_words.iter().map(|w| self.scoring.score(&self.table, w)).sum()
+    }
+}
+
+/// All the words that can be made on a table
+pub struct Solution {
+    /// Sorted alphabetically
+    pub words: Vec<String>,
+    pub total_points: u128,
+}
+
+impl Solution {
+    /// The longest word, picking the first alphabetically if there are more of the same length
+    pub fn longest_word(&self) -> Option<&str> {
+        self.words.iter().rev().max_by_key(|w| w.chars().count()).map(|s| &**s)
+    }
+    /// The words that aren't in `taken_words`, which must be sorted
+    pub fn missed_words<'a>(&'a self, taken_words: &'a [String]) -> impl Iterator<Item=&'a str> {
+        self.words.iter().filter(|w| taken_words.binary_search(w).is_err()).map(|s| &**s)
+    }
+}
+
+/// A game that can be shared between the message handler and its timer
+pub type SharedGame = Arc<Mutex<WordGameState>>;
+
+#[derive(Default)]
+/// The word games going on, at most one in each channel or thread
+pub struct WordGames {
+    games: HashMap<ChannelId, SharedGame>,
+}
+
+impl TypeMapKey for WordGames {
+    type Value = Self;
+}
+
+impl WordGames {
+    pub fn get(&self, channel_id: ChannelId) -> Option<SharedGame> {
+        self.games.get(&channel_id).cloned()
+    }
+    pub fn contains(&self, channel_id: ChannelId) -> bool {
+        self.games.contains_key(&channel_id)
+    }
+    /// Starts the game in its channel, unless there is a game there already
+    pub fn start(&mut self, wgs: WordGameState) -> Option<SharedGame> {
+        match self.games.entry(wgs.message.channel_id) {
+            Entry::Occupied(_) => None,
+            Entry::Vacant(v) => Some(v.insert(Arc::new(Mutex::new(wgs))).clone()),
         }
+    }
+    pub fn remove(&mut self, channel_id: ChannelId) -> Option<SharedGame> {
+        self.games.remove(&channel_id)
+    }
+    /// Removes the game from the channel only if it is still the given game
+    pub fn remove_game(&mut self, channel_id: ChannelId, game: &SharedGame) -> bool {
+        match self.games.entry(channel_id) {
+            Entry::Occupied(o) if Arc::ptr_eq(o.get(), game) => {
+                o.remove();
+                true
+            }
+            _ => false,
+        }
+    }
+}
 
-        if check_word(&word).await {
-            self.taken_words.insert(index_to_insert, word);
+use crate::dictionary::sprotin::{search, SprotinWord};
+use crate::http::HttpClient;
+use crate::inflection::{base_candidates, derived_forms, Derivation};
+use crate::wordlist::WordList;
 
-            let ps = self.guessers.entry(user).or_insert_with(Default::default);
-            ps.words += 1;
-            ps.letters += letter_count;
-            ps.points += ((letter_count -1) as u128).pow(2);
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why a word was accepted
+pub enum Rule {
+    /// The word is a headword in FØ-FØ
+    Headword,
+    /// Sprotin lists the word as a form of the headword
+    InflectedForm(String),
+    /// The word is derived from a form of the headword
+    Derived(Derivation, String),
+    /// The word is a headword in another dictionary
+    OtherDictionary(&'static str),
+    /// The word is made of two known words
+    Compound(String, String),
+}
 
-            Ok(())
-        } else {
-            Err(GuessError::NotFound(word))
+impl Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rule::Headword => write!(f, "headword"),
+            Rule::InflectedForm(headword) => write!(f, "form of _{}_", headword),
+            Rule::Derived(derivation, headword) => write!(f, "{} of _{}_", derivation, headword),
+            Rule::OtherDictionary(dictionary) => write!(f, "headword in {}", dictionary),
+            Rule::Compound(first, second) => write!(f, "compound of _{}_ and _{}_", first, second),
         }
     }
 }
 
-impl TypeMapKey for WordGameState {
-    type Value = Self;
+/// Dictionaries besides FØ-FØ whose Faroese headwords are also accepted
+const OTHER_DICTIONARIES: [(u8, &str); 2] = [(2, "FØ-EN"), (4, "FØ-DA")];
+
+/// Searches FØ-FØ both with and without inflections
+async fn search_words(client: &HttpClient, s: &str) -> Result<Vec<SprotinWord>, u16> {
+    let response_inflections = search(client, 1, 1, s, true, false).await?;
+    let response_sinflections = search(client, 1, 1, s, false, false).await?;
+    let mut words = response_inflections.words;
+    words.extend(response_sinflections.words);
+    Ok(words)
+}
+
+/// Adds the headword and its forms to the word list
+pub fn add_to_word_list(word_list: &WordList, word: &SprotinWord) {
+    let forms = word.inflected_form.iter().flat_map(|s| s.split('/'));
+    word_list.insert(&word.search_word, forms.chain([&*word.search_word]));
+}
+
+/// Looks up each of the words on Sprotin and adds what is found to the word list,
+/// giving how many lookups failed
+pub async fn refresh_word_list(client: &HttpClient, word_list: &WordList, words: &[String]) -> usize {
+    let mut failed = 0;
+
+    for word in words {
+        match search_words(client, word).await {
+            Ok(found) => found.iter().for_each(|w| add_to_word_list(word_list, w)),
+            Err(_) => failed += 1,
+        }
+    }
+
+    failed
+}
+
+/// The rule that accepts a word found in the word list
+fn known_rule(word_list: &WordList, s: &str) -> Option<Rule> {
+    let lemma = word_list.get(s)?;
+
+    Some(if lemma.headword == s {
+        Rule::Headword
+    } else {
+        Rule::InflectedForm(lemma.headword)
+    })
+}
+
+/// Adds the words to the word list and finds the rule that accepts `s` as one of their forms
+///
+/// Derived forms are only guessed from regular patterns, so they are checked here each time
+/// instead of being added to the word list, where they would be shown as words that could have been found.
+fn match_words(word_list: &WordList, words: &[SprotinWord], s: &str) -> Option<Rule> {
+    for word in words {
+        add_to_word_list(word_list, word);
+    }
+    known_rule(word_list, s).or_else(|| words.iter().find_map(|word| {
+        derived_forms(&word.inflected_form).into_iter()
+            .find(|(form, _)| form == s)
+            .map(|(_, derivation)| Rule::Derived(derivation, word.search_word.clone()))
+    }))
 }
 
-use crate::dictionary::sprotin::search;
+/// Whether the word is two known words put together, checked against the word list only
+///
+/// Both parts need at least three letters, since short words like "í" and "at" would let
+/// almost anything through.
+fn find_compound(word_list: &WordList, s: &str) -> Option<Rule> {
+    const MIN_PART: usize = 3;
+
+    let splits: Vec<usize> = s.char_indices().map(|(i, _)| i).collect();
+    splits.iter()
+        .skip(MIN_PART)
+        .take(splits.len().saturating_sub(2 * MIN_PART - 1))
+        .map(|&i| s.split_at(i))
+        .find(|(first, second)| word_list.contains(first) && word_list.contains(second))
+        .map(|(first, second)| Rule::Compound(first.to_owned(), second.to_owned()))
+}
 
-async fn check_word(mut s: &str) -> bool {
+/// Checks whether the word is Faroese and gives the rule that accepted it,
+/// or the status code of why Sprotin couldn't be asked.
+///
+/// Words already in the word list are accepted right away. Otherwise the word is looked up in FØ-FØ,
+/// then the words it could be derived from are, then it is tried as a compound
+/// and at last it is looked up in the other dictionaries.
+pub async fn check_word(client: &HttpClient, word_list: &WordList, mut s: &str) -> Result<Option<Rule>, u16> {
     // probably not neccessary 
     s = s.trim();
-    let words = {
-        let response_inflections = search(1, 1, s, true, false).await.unwrap();
-        let response_sinflections = search(1, 1, s, false, false).await.unwrap();
-        let mut words = response_inflections.words;
-        words.extend(response_sinflections.words);
-        words
-    };
 
-    for word in words {
-        if s == word.search_word {
-            return true;
+    if let Some(rule) = known_rule(word_list, s) {
+        return Ok(Some(rule));
+    }
+
+    let words = search_words(client, s).await?;
+    if let Some(rule) = match_words(word_list, &words, s) {
+        return Ok(Some(rule));
+    }
+
+    for base in base_candidates(s) {
+        let words = search_words(client, &base).await?;
+        if let Some(rule @ Rule::Derived(..)) = match_words(word_list, &words, s) {
+            return Ok(Some(rule));
         }
-        if word.inflected_form.iter().map(|s| s.split('/')).flatten().map(|s| s.trim()).any(|w| w == s) {
-            return true;
+    }
+
+    if let Some(rule) = find_compound(word_list, s) {
+        return Ok(Some(rule));
+    }
+
+    for (id, name) in OTHER_DICTIONARIES {
+        let res = search(client, id, 1, s, false, false).await?;
+        if res.words.iter().any(|w| w.search_word == s) {
+            return Ok(Some(Rule::OtherDictionary(name)));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serenity::model::{channel::Message, id::UserId};
+
+    use super::{can_trace, find_compound, fits_letters, match_words, format_table, gen_dice_table, gen_table, pick_table, corpus_weights, letter_value, trace_path, Bonus, Generation, Mode, Points, Rule, Scoring, Seed, SeedWeights, Table, WordGameState, DICE, LETTER_WEIGHTS, MAX_REROLLS, MIN_SOLVABLE_WORDS};
+    use crate::dictionary::sprotin::SprotinWord;
+    use crate::inflection::Derivation;
+    use crate::wordlist::WordList;
+
+    fn table() -> Table {
+        Table::new(4, vec![
+            'h', 'e', 's', 't',
+            'a', 'r', 'u', 'm',
+            'k', 'ø', 'n', 'i',
+            'l', 'a', 'd', 'o',
+        ])
+    }
+
+    #[test]
+    fn traced_paths() {
+        let path = |word: &str| trace_path(&table(), &word.chars().collect::<Vec<_>>());
+
+        assert_eq!(path("hest"), Some(vec![0, 1, 2, 3]));
+        assert_eq!(path("mund"), Some(vec![7, 6, 10, 14]));
+        assert_eq!(path("tak"), None);
+        assert_eq!(path(""), None);
+    }
+
+    fn trace(word: &str) -> bool {
+        can_trace(&table(), &word.chars().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn traces_through_neighbours() {
+        assert!(trace("hest"));
+        assert!(trace("ørs"));
+        assert!(trace("run"));
+        assert!(trace("mund"));
+        assert!(trace("kla"));
+    }
+    #[test]
+    fn rejects_far_apart_letters() {
+        assert!(!trace("tak"));
+        assert!(!trace("hm"));
+        assert!(!trace("ston"));
+    }
+    #[test]
+    fn fits_letters_as_multiset() {
+        let fits = |word: &str| fits_letters(&table(), &word.chars().collect::<Vec<_>>());
+
+        assert!(fits("ston"));
+        assert!(fits("anda"));
+        assert!(!fits("sess"));
+        assert!(!fits("bók"));
+    }
+    #[test]
+    fn rejects_reused_cells() {
+        assert!(!trace("hesse"));
+        assert!(!trace("rar"));
+    }
+    #[test]
+    fn compounds_of_known_words() {
+        let word_list = WordList::default();
+        word_list.insert("hestur", ["hesta"]);
+        word_list.insert("vognur", ["vognur"]);
+        word_list.insert("ró", ["ró"]);
+        word_list.insert("ár", ["ár"]);
+        word_list.insert("at", ["at"]);
+        word_list.insert("í", ["í"]);
+        word_list.insert("á", ["á"]);
+
+        assert_eq!(find_compound(&word_list, "hestavognur"), Some(Rule::Compound("hesta".to_owned(), "vognur".to_owned())));
+        assert_eq!(find_compound(&word_list, "hestavogn"), None);
+        assert_eq!(find_compound(&word_list, "vognur"), None);
+        // Short words aren't parts of compounds
+        assert_eq!(find_compound(&word_list, "róár"), None);
+        assert_eq!(find_compound(&word_list, "atár"), None);
+        assert_eq!(find_compound(&word_list, "ívognur"), None);
+        assert_eq!(find_compound(&word_list, "hestaat"), None);
+        assert_eq!(find_compound(&word_list, "áí"), None);
+    }
+    #[test]
+    fn derived_forms_not_kept() {
+        let word_list = WordList::default();
+        let mut forms = vec!["ríkur".to_owned()];
+        forms.resize(24, "ríkt".to_owned());
+        let rikur: SprotinWord = serde_json::from_value(serde_json::json!({
+            "Id": 1,
+            "SearchWord": "ríkur",
+            "DisplayWord": "ríkur",
+            "InflectedForm": forms,
+            "Explanation": "",
+            "Index": 1,
+            "Date": "2024-05-15 12:00:00",
+            "Groups": [],
+        })).unwrap();
+
+        assert_eq!(match_words(&word_list, &[rikur], "ríkari"), Some(Rule::Derived(Derivation::Comparative, "ríkur".to_owned())));
+        assert!(word_list.contains("ríkt"));
+        assert!(!word_list.contains("ríkari"));
+        assert_eq!(find_compound(&word_list, "ríkariríkt"), None);
+    }
+    #[test]
+    fn larger_tables() {
+        let table = Table::new(5, "abcdefghijklmnopqrstuvwxy".chars().collect());
+
+        assert_eq!(format_table(&table), "```\na b c d e\nf g h i j\nk l m n o\np q r s t\nu v w x y\n```");
+        assert!(can_trace(&table, &['a', 'g', 'm', 's', 'y']));
+        assert!(can_trace(&table, &['e', 'j', 'o', 't', 'y']));
+        assert!(!can_trace(&table, &['e', 'f']));
+        assert_eq!(table.min_letters(), 3);
+        assert_eq!(Scoring::Squared.score(&table, "abc"), 1);
+        assert_eq!(Scoring::Squared.score(&table, "abcde"), 9);
+        assert_eq!(gen_dice_table(6, &mut rand::rng()).cells().len(), 36);
+    }
+    #[test]
+    fn dice_tables_use_each_die() {
+        /// Whether each of the letters can be given its own die
+        fn assign(letters: &[char], dice: &mut Vec<&[char; 6]>) -> bool {
+            let Some((c, rest)) = letters.split_first() else {
+                return true;
+            };
+            for i in 0..dice.len() {
+                if dice[i].contains(c) {
+                    let die = dice.remove(i);
+                    if assign(rest, dice) {
+                        return true;
+                    }
+                    dice.insert(i, die);
+                }
+            }
+            false
         }
+
+        let table = gen_dice_table(4, &mut rand::rng());
+        assert!(assign(table.cells(), &mut DICE.iter().collect()));
     }
-    false
-}
\ No newline at end of file
+    #[test]
+    fn weights_from_corpus() {
+        let word_list = WordList::default();
+        assert_eq!(corpus_weights(&word_list), None);
+
+        let words: Vec<String> = (0..2_000usize)
+            .map(|i| (0..5).fold("hest".to_owned(), |w, d| w + ["a", "e", "i", "o", "u"][i / 5usize.pow(d) % 5]))
+            .collect();
+        word_list.insert("hestur", words.iter().map(|w| &**w));
+
+        let weights = corpus_weights(&word_list).unwrap();
+        let weight = |c| weights.iter().find(|w| w.0 == c).unwrap().1;
+        assert_eq!(weight('h'), 2_000);
+        assert_eq!(weight('ø'), 1);
+        assert_eq!(weight('b'), 1);
+    }
+    #[test]
+    fn rerolls_for_playable_tables() {
+        let word_list = WordList::default();
+        // Every pair of two different letters of abcde, so only a table with all five has enough words
+        for a in "abcde".chars() {
+            for b in "abcde".chars().filter(|&b| b != a) {
+                let word = format!("{a}{b}");
+                word_list.insert(&word, [word.as_str()]);
+            }
+        }
+        let table = |letters: &str| {
+            let mut cells: Vec<char> = letters.chars().collect();
+            cells.resize(16, 'x');
+            Table::new(4, cells)
+        };
+        let seed = |value| Seed { width: 4, generation: Generation::Dice, value, weights: None };
+        let scripted = |tables: Vec<Table>| {
+            let mut rolls = tables.into_iter().enumerate().map(|(i, t)| (t, seed(i as u64))).cycle();
+            move || rolls.next().unwrap()
+        };
+
+        let mut rolls = 0;
+        let mut gen = scripted(vec![table(""), table("ab"), table("abcde"), table("abcde")]);
+        let (picked, picked_seed) = pick_table(|| { rolls += 1; gen() }, MIN_SOLVABLE_WORDS, Mode::Free, &word_list);
+        assert_eq!(picked, table("abcde"));
+        assert_eq!(picked_seed, seed(2));
+        assert_eq!(rolls, 3);
+        assert!(super::solve(&picked, Mode::Free, &word_list).len() >= MIN_SOLVABLE_WORDS);
+
+        // When no table is good enough, the one with the most words is kept
+        let mut rolls = 0;
+        let mut gen = scripted(vec![table(""), table("abc"), table("ab")]);
+        let (picked, picked_seed) = pick_table(|| { rolls += 1; gen() }, MIN_SOLVABLE_WORDS, Mode::Free, &word_list);
+        assert_eq!(picked, table("abc"));
+        assert_eq!(picked_seed, seed(1));
+        assert_eq!(rolls, MAX_REROLLS);
+    }
+    #[test]
+    fn seeds_replay_tables() {
+        let word_list = WordList::default();
+        let (table, seed) = gen_table(5, Generation::Letters, Mode::Free, &word_list);
+        let parsed: Seed = seed.to_string().parse().unwrap();
+
+        assert_eq!(parsed, seed);
+        assert_eq!(parsed.table(), table);
+        assert_eq!("4dff".parse(), Ok(Seed { width: 4, generation: Generation::Dice, value: 255, weights: None }));
+        assert_eq!("3dff".parse::<Seed>(), Err(()));
+        assert_eq!("4x".parse::<Seed>(), Err(()));
+        // Letter seeds need all their weights
+        assert_eq!("4lff".parse::<Seed>(), Err(()));
+        assert_eq!("4lff-fff".parse::<Seed>(), Err(()));
+    }
+    #[test]
+    fn seeds_keep_weights() {
+        let word_list = WordList::default();
+        let words: Vec<String> = (0..2_000usize)
+            .map(|i| (0..5).fold("hest".to_owned(), |w, d| w + ["a", "e", "i", "o", "u"][i / 5usize.pow(d) % 5]))
+            .collect();
+        word_list.insert("hestur", words.iter().map(|w| &**w));
+        let (table, seed) = gen_table(4, Generation::Letters, Mode::Free, &word_list);
+
+        // The word list changing doesn't change the table of the seed
+        word_list.insert("ø", ["ø"]);
+        word_list.insert("býður", ["býður", "bjóða", "bjóðar"]);
+        let parsed: Seed = seed.to_string().parse().unwrap();
+        assert_eq!(parsed.table(), table);
+
+        let weights = seed.weights.unwrap().weights();
+        let weight = |c| weights.iter().find(|w| w.0 == c).unwrap().1;
+        assert_eq!(weight('e'), 362);
+        assert_eq!(weight('h'), 181);
+        assert_eq!(weight('ø'), 2);
+        assert_eq!(SeedWeights::new(&LETTER_WEIGHTS).weights().len(), LETTER_WEIGHTS.len());
+    }
+    #[test]
+    fn daily_seeds() {
+        let day = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+
+        let empty = WordList::default();
+
+        assert_eq!(Seed::daily(day, &empty), Seed::daily(day, &empty));
+        assert_ne!(Seed::daily(day, &empty), Seed::daily(day.succ_opt().unwrap(), &empty));
+        assert_eq!(Seed::daily(day, &empty).table(), Seed::daily(day, &empty).table());
+    }
+    #[test]
+    fn daily_boards_have_enough_words() {
+        let word_list = WordList::default();
+        // Every pair of two letters from some of the dice letters
+        for a in "aeiknrst".chars() {
+            for b in "aeiknrst".chars() {
+                let word = format!("{a}{b}");
+                word_list.insert(&word, [word.as_str()]);
+            }
+        }
+        let words = |seed: Seed| super::solve(&seed.table(), Mode::Boggle, &word_list).len();
+
+        let days = (1..=28).map(|d| NaiveDate::from_ymd_opt(2024, 2, d).unwrap());
+        let mut rerolled = 0;
+        for day in days {
+            let seed = Seed::daily(day, &word_list);
+            assert!(words(seed) >= MIN_SOLVABLE_WORDS, "{day} has too few words");
+            assert_eq!(Seed::daily(day, &word_list), seed);
+            if seed != Seed::daily(day, &WordList::default()) {
+                rerolled += 1;
+            }
+        }
+        assert!(rerolled > 0);
+    }
+    #[test]
+    fn scoring_systems() {
+        let table = table();
+
+        assert_eq!(Scoring::Squared.score(&table, "ró"), 1);
+        assert_eq!(Scoring::Squared.score(&table, "hestur"), 25);
+        assert_eq!(Scoring::Brackets.score(&table, "ró"), 1);
+        assert_eq!(Scoring::Brackets.score(&table, "hest"), 1);
+        assert_eq!(Scoring::Brackets.score(&table, "hestavognur"), 11);
+        // The same points for each length as in the board game
+        let points: Vec<u128> = (2..=9).map(|n| Scoring::Brackets.score(&table, &"a".repeat(n))).collect();
+        assert_eq!(points, [1, 1, 1, 2, 3, 5, 11, 11]);
+
+        assert_eq!(letter_value('a'), 1);
+        assert!(letter_value('ý') > letter_value('d'));
+        assert_eq!(letter_value('q'), 10);
+        assert_eq!(Scoring::Rarity.score(&table, "ár"), letter_value('á') + letter_value('r'));
+    }
+    #[test]
+    fn bonuses() {
+        let (anna, jogvan) = (UserId::new(1), UserId::new(2));
+        let seed = Seed { width: 4, generation: Generation::Dice, value: 0, weights: None };
+        let game = |bonus| {
+            let mut wgs = WordGameState::new(table(), Some(seed), None, Mode::Free, anna, Message::default())
+                .with_scoring(Scoring::Squared, bonus);
+            // Anna found both words first and only she found "hestur"
+            wgs.finders.insert("hestur".to_owned(), vec![anna]);
+            wgs.finders.insert("ró".to_owned(), vec![anna, jogvan]);
+            wgs.guessers.insert(anna, Points { points: 26, ..Points::default() });
+            wgs.guessers.insert(jogvan, Points { points: 1, ..Points::default() });
+            wgs.give_bonuses();
+            (wgs.guessers[&anna].bonus, wgs.guessers[&jogvan].bonus)
+        };
+
+        assert_eq!(game(Bonus::None), (0, 0));
+        assert_eq!(game(Bonus::FirstFinder), (26, 0));
+        assert_eq!(game(Bonus::Unique), (25, 0));
+    }
+}
@@ -0,0 +1,328 @@
+//! A puzzle where a five-letter Faroese word has to be guessed in six tries.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::{fs, io};
+
+use chrono::NaiveDate;
+use rand::{rng, Rng, SeedableRng};
+use rand::seq::IndexedRandom;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use serenity::model::id::UserId;
+use serenity::prelude::TypeMapKey;
+use tokio::sync::Mutex;
+
+use crate::http::HttpClient;
+use crate::wordgame::{alphabet, check_word, daily_number};
+use crate::wordlist::WordList;
+
+const DAILY_FILE: &str = "wordle_daily.json";
+
+pub const WORD_LENGTH: usize = 5;
+pub const MAX_GUESSES: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// How a letter of a guess matches the answer, ordered from worst to best
+pub enum Tile {
+    /// The letter isn't in the answer, or not as many times as in the guess
+    Absent,
+    /// The letter is in the answer but somewhere else
+    Present,
+    /// The letter is in the same place in the answer
+    Correct,
+}
+
+impl Tile {
+    pub fn emoji(self) -> char {
+        match self {
+            Tile::Absent => '⬛',
+            Tile::Present => '🟨',
+            Tile::Correct => '🟩',
+        }
+    }
+}
+
+/// Matches the guess against the answer, both `WORD_LENGTH` letters long
+///
+/// Letters that are in the right place are marked first, so a letter that is in the guess
+/// more times than in the answer is only marked present as many times as it is left over.
+pub fn score_guess(answer: &[char], guess: &[char]) -> [Tile; WORD_LENGTH] {
+    let mut tiles = [Tile::Absent; WORD_LENGTH];
+    let mut left_over: Vec<char> = Vec::with_capacity(WORD_LENGTH);
+
+    for (i, (&a, &g)) in answer.iter().zip(guess).enumerate() {
+        if a == g {
+            tiles[i] = Tile::Correct;
+        } else {
+            left_over.push(a);
+        }
+    }
+    for (tile, g) in tiles.iter_mut().zip(guess) {
+        if *tile == Tile::Correct {
+            continue;
+        }
+        if let Some(i) = left_over.iter().position(|c| c == g) {
+            left_over.swap_remove(i);
+            *tile = Tile::Present;
+        }
+    }
+
+    tiles
+}
+
+/// Headwords in the word list that can be the answer
+pub fn answers(word_list: &WordList) -> Vec<String> {
+    word_list.headwords().into_iter()
+        .filter(|w| w.chars().count() == WORD_LENGTH && w.chars().all(|c| alphabet().any(|a| a == c)))
+        .collect()
+}
+
+/// Picks the answer of the daily puzzle, which depends on the word list
+///
+/// Use [`Wordles::daily_answer`] so it stays the same through the day as the word list grows.
+pub fn daily_answer(answers: &[String], date: NaiveDate) -> Option<&str> {
+    if answers.is_empty() {
+        return None;
+    }
+    let i = ChaCha8Rng::seed_from_u64(daily_number(date)).random_range(0..answers.len());
+    Some(&answers[i])
+}
+
+/// A random answer
+pub fn random_answer(answers: &[String]) -> Option<&str> {
+    answers.choose(&mut rng()).map(|s| &**s)
+}
+
+pub enum WordleError {
+    WrongLength,
+    /// The guess has letters that aren't in the Faroese alphabet
+    NotFaroese,
+    AlreadyGuessed,
+    NotFound,
+    /// Sprotin couldn't be asked, with the status code of why
+    LookupFailed(u16),
+}
+
+pub struct Wordle {
+    answer: Vec<char>,
+    /// The day of the daily puzzle if this is one
+    pub daily: Option<NaiveDate>,
+    pub guesses: Vec<(String, [Tile; WORD_LENGTH])>,
+}
+
+impl Wordle {
+    pub fn new(answer: &str, daily: Option<NaiveDate>) -> Self {
+        Wordle { answer: answer.chars().collect(), daily, guesses: Vec::new() }
+    }
+    pub fn answer(&self) -> String {
+        self.answer.iter().collect()
+    }
+    pub fn is_won(&self) -> bool {
+        self.guesses.last().is_some_and(|(_, tiles)| tiles.iter().all(|&t| t == Tile::Correct))
+    }
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.guesses.len() >= MAX_GUESSES
+    }
+    /// Checks the guess the same way as in the word game and adds it if it is a word
+    pub async fn guess(&mut self, client: &HttpClient, word_list: &WordList, guess: &str) -> Result<[Tile; WORD_LENGTH], WordleError> {
+        let guess = guess.to_lowercase();
+        let chars: Vec<char> = guess.chars().collect();
+
+        if chars.len() != WORD_LENGTH {
+            return Err(WordleError::WrongLength);
+        }
+        if !chars.iter().all(|&c| alphabet().any(|a| a == c)) {
+            return Err(WordleError::NotFaroese);
+        }
+        if self.guesses.iter().any(|(g, _)| *g == guess) {
+            return Err(WordleError::AlreadyGuessed);
+        }
+        if chars != self.answer && check_word(client, word_list, &guess).await.map_err(WordleError::LookupFailed)?.is_none() {
+            return Err(WordleError::NotFound);
+        }
+
+        let tiles = score_guess(&self.answer, &chars);
+        self.guesses.push((guess, tiles));
+        Ok(tiles)
+    }
+    /// What is known about each letter of the alphabet, `None` if it hasn't been guessed
+    pub fn keyboard(&self) -> Vec<(char, Option<Tile>)> {
+        alphabet().map(|c| {
+            let best = self.guesses.iter()
+                .flat_map(|(g, tiles)| g.chars().zip(tiles.iter().copied()))
+                .filter(|&(g, _)| g == c)
+                .map(|(_, t)| t)
+                .max();
+            (c, best)
+        }).collect()
+    }
+    /// The guesses so far with their tiles, one per line
+    pub fn board(&self) -> String {
+        let mut s = String::new();
+        for (guess, tiles) in &self.guesses {
+            s.extend(tiles.iter().map(|t| t.emoji()));
+            s.push_str(&format!(" `{}`\n", guess.to_uppercase()));
+        }
+        s
+    }
+    /// The tiles without the letters, to share how it went without giving the answer away
+    pub fn share(&self) -> String {
+        let tries = if self.is_won() { self.guesses.len().to_string() } else { "X".to_owned() };
+        let mut s = match self.daily {
+            Some(date) => format!("Orðla {} {}/{}\n", date, tries, MAX_GUESSES),
+            None => format!("Orðla {}/{}\n", tries, MAX_GUESSES),
+        };
+
+        for (_, tiles) in &self.guesses {
+            s.extend(tiles.iter().map(|t| t.emoji()));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+/// A puzzle that can be shared between the commands of its player
+pub type SharedWordle = Arc<Mutex<Wordle>>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+/// What is kept on disk about the daily puzzles, so restarts don't change or reset them
+struct Daily {
+    /// The answer of the latest daily puzzle, fixed when it was first started
+    answer: Option<(NaiveDate, String)>,
+    /// The last daily puzzle each user started, so it can only be played once
+    played: HashMap<UserId, NaiveDate>,
+}
+
+#[derive(Default)]
+/// The puzzles being played, one for each user
+pub struct Wordles {
+    games: HashMap<UserId, SharedWordle>,
+    daily: Daily,
+}
+
+impl TypeMapKey for Wordles {
+    type Value = Self;
+}
+
+impl Wordles {
+    /// Loads the daily puzzles from disk, or starts without any if there are none
+    pub fn load() -> Self {
+        let daily = match fs::read_to_string(DAILY_FILE) {
+            Ok(s) => serde_json::from_str(&s).unwrap_or_else(|e| {
+                eprintln!("Could not read {}, starting without daily puzzles: {}", DAILY_FILE, e);
+                Daily::default()
+            }),
+            Err(_) => Daily::default(),
+        };
+        Wordles { games: HashMap::new(), daily }
+    }
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(DAILY_FILE, serde_json::to_string(&self.daily)?)
+    }
+
+    pub fn get(&self, user: UserId) -> Option<SharedWordle> {
+        self.games.get(&user).cloned()
+    }
+    pub fn has_played_daily(&self, user: UserId, date: NaiveDate) -> bool {
+        self.daily.played.get(&user) == Some(&date)
+    }
+    /// The answer of the daily puzzle of the date, picked from `answers` the first time it is asked for
+    pub fn daily_answer(&mut self, date: NaiveDate, answers: &[String]) -> Option<String> {
+        match &self.daily.answer {
+            Some((day, answer)) if *day == date => Some(answer.clone()),
+            _ => {
+                let answer = daily_answer(answers, date)?.to_owned();
+                self.daily.answer = Some((date, answer.clone()));
+                Some(answer)
+            }
+        }
+    }
+    /// Starts the puzzle for the user, replacing any they had
+    pub fn start(&mut self, user: UserId, wordle: Wordle) {
+        if let Some(date) = wordle.daily {
+            self.daily.played.insert(user, date);
+        }
+        self.games.insert(user, Arc::new(Mutex::new(wordle)));
+    }
+    pub fn remove(&mut self, user: UserId) -> Option<SharedWordle> {
+        self.games.remove(&user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{answers, daily_answer, score_guess, Tile, Wordle, Wordles};
+    use crate::wordlist::WordList;
+
+    use Tile::*;
+
+    fn score(answer: &str, guess: &str) -> [Tile; 5] {
+        score_guess(&answer.chars().collect::<Vec<_>>(), &guess.chars().collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn scores_guesses() {
+        assert_eq!(score("hesta", "hesta"), [Correct; 5]);
+        assert_eq!(score("seyða", "hesta"), [Absent, Correct, Present, Absent, Correct]);
+        assert_eq!(score("rósin", "sólin"), [Present, Correct, Absent, Correct, Correct]);
+    }
+    #[test]
+    fn repeated_letters() {
+        // Only one of the a's is left over after the one in the right place
+        assert_eq!(score("aldan", "aaaaa"), [Correct, Absent, Absent, Correct, Absent]);
+        assert_eq!(score("banka", "aabbb"), [Present, Correct, Present, Absent, Absent]);
+    }
+    #[test]
+    fn keyboard_keeps_best() {
+        let mut wordle = Wordle::new("rósin", None);
+        wordle.guesses.push(("sólin".to_owned(), score("rósin", "sólin")));
+        wordle.guesses.push(("sjúkt".to_owned(), score("rósin", "sjúkt")));
+        let keyboard = wordle.keyboard();
+        let key = |c| keyboard.iter().find(|k| k.0 == c).unwrap().1;
+
+        assert_eq!(key('s'), Some(Present));
+        assert_eq!(key('ó'), Some(Correct));
+        assert_eq!(key('l'), Some(Absent));
+        assert_eq!(key('ð'), None);
+        assert!(!wordle.is_over());
+    }
+    #[test]
+    fn shares_without_letters() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        let mut wordle = Wordle::new("rósin", Some(date));
+        wordle.guesses.push(("sólin".to_owned(), score("rósin", "sólin")));
+        wordle.guesses.push(("rósin".to_owned(), score("rósin", "rósin")));
+
+        assert!(wordle.is_won());
+        assert_eq!(wordle.share(), "Orðla 2024-05-15 2/6\n🟨🟩⬛🟩🟩\n🟩🟩🟩🟩🟩\n");
+    }
+    #[test]
+    fn picks_answers() {
+        let word_list = WordList::default();
+        word_list.insert("hestur", ["hestur", "hesta"]);
+        word_list.insert("rósin", ["rósin"]);
+        word_list.insert("bók", ["bók"]);
+        let answers = answers(&word_list);
+        let date = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+
+        assert_eq!(answers, vec!["rósin"]);
+        assert_eq!(daily_answer(&answers, date), Some("rósin"));
+        assert_eq!(daily_answer(&[], date), None);
+    }
+    #[test]
+    fn daily_answer_stays() {
+        let mut wordles = Wordles::default();
+        let date = NaiveDate::from_ymd_opt(2024, 5, 15).unwrap();
+        let mut answers = vec!["rósin".to_owned()];
+
+        assert_eq!(wordles.daily_answer(date, &answers).as_deref(), Some("rósin"));
+        // New words later in the day don't change the puzzle
+        answers.extend(["hesta", "sólin", "seyða"].map(str::to_owned));
+        assert_eq!(wordles.daily_answer(date, &answers).as_deref(), Some("rósin"));
+        assert_eq!(wordles.daily_answer(date, &[]).as_deref(), Some("rósin"));
+        assert_eq!(wordles.daily_answer(date.succ_opt().unwrap(), &[]), None);
+    }
+}
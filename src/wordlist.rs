@@ -0,0 +1,153 @@
+//! A lexicon of known Faroese words and inflected forms, gathered from Sprotin and kept on disk.
+
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fs,
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
+
+use serenity::prelude::TypeMapKey;
+
+const WORD_LIST_FILE: &str = "word_list.txt";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Where a form in the word list comes from
+pub struct Lemma {
+    pub headword: String,
+}
+
+#[derive(Debug, Clone, Default)]
+/// The cached word list, mapping each form to its headword
+///
+/// Cloning is cheap and all clones share the same list.
+pub struct WordList {
+    words: Arc<RwLock<BTreeMap<String, Lemma>>>,
+    changed: Arc<AtomicBool>,
+}
+
+impl TypeMapKey for WordList {
+    type Value = Self;
+}
+
+/// Whether the word is made only of letters and so can be on a board
+fn is_playable(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(char::is_alphabetic)
+}
+
+/// Reads a line of the form `form[\theadword]`, where a missing headword means it is the form itself
+///
+/// Lines with a derivation after the headword are from before derived forms were left out
+/// of the list, so they are skipped.
+fn parse_line(line: &str) -> Option<(String, Lemma)> {
+    let mut fields = line.split('\t').map(str::trim);
+    let form = fields.next().filter(|w| is_playable(w))?;
+    let headword = fields.next().filter(|h| !h.is_empty()).unwrap_or(form);
+    if fields.next().is_some_and(|d| !d.is_empty()) {
+        return None;
+    }
+
+    Some((form.to_owned(), Lemma { headword: headword.to_owned() }))
+}
+
+impl WordList {
+    /// Loads the list from disk, or starts an empty one if there is none
+    pub fn load() -> Self {
+        let words = match fs::read_to_string(WORD_LIST_FILE) {
+            Ok(s) => s.lines().filter_map(parse_line).collect(),
+            Err(_) => BTreeMap::new(),
+        };
+
+        WordList {
+            words: Arc::new(RwLock::new(words)),
+            changed: Arc::default(),
+        }
+    }
+    /// Saves the list to disk if any words were added since it was last saved
+    pub fn save(&self) -> io::Result<()> {
+        if !self.changed.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let words = self.words.read().unwrap();
+        let mut s = String::new();
+        for (form, Lemma { headword }) in words.iter() {
+            s.push_str(form);
+            if form != headword {
+                s.push('\t');
+                s.push_str(headword);
+            }
+            s.push('\n');
+        }
+        fs::write(WORD_LIST_FILE, s)
+    }
+
+    /// Adds the forms of a headword that can be played, lowercased
+    ///
+    /// Only forms listed by Sprotin should be added, since everything in the list is accepted
+    /// without asking and shown as words that could have been found.
+    /// A form that is already known keeps its headword.
+    pub fn insert<'a, I: IntoIterator<Item=&'a str>>(&self, headword: &str, forms: I) {
+        let mut list = self.words.write().unwrap();
+
+        for form in forms.into_iter().map(str::trim).filter(|w| is_playable(w)) {
+            if let Entry::Vacant(v) = list.entry(form.to_lowercase()) {
+                v.insert(Lemma { headword: headword.to_owned() });
+                self.changed.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.read().unwrap().contains_key(word)
+    }
+    pub fn get(&self, word: &str) -> Option<Lemma> {
+        self.words.read().unwrap().get(word).cloned()
+    }
+    pub fn len(&self) -> usize {
+        self.words.read().unwrap().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.words.read().unwrap().is_empty()
+    }
+    /// All the headwords, each once
+    pub fn headwords(&self) -> Vec<String> {
+        let mut headwords: Vec<_> = self.words.read().unwrap().values().map(|l| l.headword.clone()).collect();
+        headwords.sort_unstable();
+        headwords.dedup();
+        headwords
+    }
+    /// All the words for which `f` returns true
+    pub fn filter<F: FnMut(&str) -> bool>(&self, mut f: F) -> Vec<String> {
+        self.words.read().unwrap().keys().filter(|w| f(w)).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_line, Lemma, WordList};
+
+    #[test]
+    fn lines() {
+        let lemma = |headword: &str| Lemma { headword: headword.to_owned() };
+
+        assert_eq!(parse_line("hestur"), Some(("hestur".to_owned(), lemma("hestur"))));
+        assert_eq!(parse_line("hesti\thestur"), Some(("hesti".to_owned(), lemma("hestur"))));
+        assert_eq!(parse_line("hesti\thestur\t"), Some(("hesti".to_owned(), lemma("hestur"))));
+        // Derived forms saved by older versions are dropped
+        assert_eq!(parse_line("kallast\tkalla\tmediopassive"), None);
+        assert_eq!(parse_line("to orð"), None);
+        assert_eq!(parse_line(""), None);
+    }
+    #[test]
+    fn first_headword_stays() {
+        let word_list = WordList::default();
+        word_list.insert("kallast", ["kallast"]);
+        word_list.insert("kalla", ["kalla", "Kallast"]);
+
+        assert_eq!(word_list.get("kallast").unwrap().headword, "kallast");
+        assert_eq!(word_list.headwords(), vec!["kalla", "kallast"]);
+    }
+}